@@ -0,0 +1,77 @@
+use crate::{
+    i18n::{Locale, Msg},
+    save::SaveGame,
+    symbols::SymbolSet,
+};
+
+/// Position of the square at `x`,`y` as read out by screen readers, counting from 1
+pub fn position(locale: Locale, x: usize, y: usize) -> String {
+    locale.t(Msg::Position { x, y })
+}
+
+/// Label of the square at `x`,`y` for screen readers, e.g.
+/// "row 3, column 5, empty, candidates 2 4 7", which names the digits by the `symbols` shown in
+/// the grid
+pub fn square_label(
+    locale: Locale,
+    symbols: SymbolSet,
+    game: &SaveGame,
+    x: usize,
+    y: usize,
+    show_wrong: bool,
+) -> String {
+    let i = x + 9 * y;
+    let mut parts = vec![position(locale, x, y)];
+    match game.sudoku.get(x, y) {
+        0 => parts.push(locale.t(Msg::Empty)),
+        val if game.givens[i] => {
+            parts.extend([symbols.symbol(val).to_string(), locale.t(Msg::Given)])
+        }
+        val => parts.push(symbols.symbol(val).to_string()),
+    }
+    if show_wrong && game.is_wrong(i) {
+        parts.push(locale.t(Msg::Wrong));
+    }
+    let candidates: Vec<&str> = (1..=9u8)
+        .filter(|val| game.has_note(i, *val))
+        .map(|val| symbols.symbol(val))
+        .collect();
+    if !candidates.is_empty() {
+        parts.push(locale.t(Msg::Candidates(&candidates.join(" "))));
+    }
+    if game.colours[i] > 0 {
+        parts.push(locale.t(Msg::Colour(game.colours[i])));
+    }
+    parts.join(", ")
+}
+
+/// Announcement of the units containing the square at `x`,`y` that were completed by the last
+/// move, given which of its row, column and box were filled `before` and `after` the move
+pub fn completed_units(
+    locale: Locale,
+    x: usize,
+    y: usize,
+    before: [bool; 3],
+    after: [bool; 3],
+) -> Option<String> {
+    let names = [
+        locale.t(Msg::Row(y + 1)),
+        locale.t(Msg::Column(x + 1)),
+        locale.t(Msg::Box(3 * (y / 3) + x / 3 + 1)),
+    ];
+    let completed: Vec<&str> = names
+        .iter()
+        .zip(before.iter().zip(after))
+        .filter(|(_, (before, after))| !**before && *after)
+        .map(|(name, _)| name.as_str())
+        .collect();
+    let list = match completed.as_slice() {
+        [] => return None,
+        [one] => one.to_string(),
+        [init @ .., last] => locale.t(Msg::List {
+            init: &init.join(", "),
+            last,
+        }),
+    };
+    Some(locale.t(Msg::Completed(&list)))
+}
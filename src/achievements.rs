@@ -0,0 +1,252 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use dioxus::prelude::*;
+use dioxus_sdk::utils::timing::use_debounce;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::{
+    i18n::{use_t, Locale, Msg},
+    stats::Stats,
+    storage::Versioned,
+    timer::local_day,
+    Difficulty,
+};
+
+// SETTINGS
+
+/// Time limit in milliseconds for [`Achievement::FastChallenge`]
+pub const FAST_CHALLENGE_MS: u64 = 10 * 60_000;
+/// Number of times the cat has to purr for [`Achievement::Petting`]
+pub const PURRS: u32 = 100;
+/// Length in days of the streak for [`Achievement::MonthStreak`]
+pub const MONTH_STREAK: u32 = 30;
+/// Duration in milliseconds a toast is shown for
+pub const TOAST_DURATION: u64 = 4000;
+
+/// Goals that are unlocked once and shown in the gallery with the day they were reached
+#[derive(
+    EnumIter, Display, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum Achievement {
+    /// win a Challenge in less than [`FAST_CHALLENGE_MS`]
+    #[strum(to_string = "Lightning fast")]
+    FastChallenge,
+    /// win a Hard puzzle without hints
+    #[strum(to_string = "No help needed")]
+    NoHintHard,
+    /// make the cat purr [`PURRS`] times
+    #[strum(to_string = "Cat whisperer")]
+    Petting,
+    /// complete the rows from top to bottom, the columns from left to right and the cells in
+    /// row-major order within one game
+    #[strum(to_string = "Neat and tidy")]
+    UnitsInOrder,
+    /// reach a streak of [`MONTH_STREAK`] days
+    #[strum(to_string = "Month of puzzles")]
+    MonthStreak,
+}
+impl Achievement {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Achievement::FastChallenge => "⚡",
+            Achievement::NoHintHard => "🧠",
+            Achievement::Petting => "💕",
+            Achievement::UnitsInOrder => "📐",
+            Achievement::MonthStreak => "📅",
+        }
+    }
+}
+
+/// Unlocked achievements and the progress towards the others, persisted in local storage
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Achievements {
+    /// the day each achievement was unlocked on, as returned by [`local_day`]
+    pub unlocked: BTreeMap<Achievement, i64>,
+    /// number of times the cat purred
+    pub purrs: u32,
+    /// whether all units were completed in order so far in the current game
+    pub in_order: bool,
+}
+impl Achievements {
+    /// Unlock the `achievement` on `today`, returning it if it is new
+    fn unlock(&mut self, achievement: Achievement, today: i64) -> Option<Achievement> {
+        if self.unlocked.contains_key(&achievement) {
+            return None;
+        }
+        self.unlocked.insert(achievement, today);
+        Some(achievement)
+    }
+    /// Start tracking a new game
+    pub fn start(&mut self) {
+        self.in_order = true;
+    }
+    /// Track a placement that newly `completed` the row, column or cell containing it, where
+    /// `filled_before` tells whether all units of the same kind before it were filled
+    pub fn placed(&mut self, completed: [bool; 3], filled_before: [bool; 3]) {
+        if (0..3).any(|kind| completed[kind] && !filled_before[kind]) {
+            self.in_order = false;
+        }
+    }
+    /// Track a won game, returning the newly unlocked achievements
+    pub fn won(
+        &mut self,
+        difficulty: Difficulty,
+        elapsed_ms: u64,
+        hints: u32,
+        today: i64,
+    ) -> Vec<Achievement> {
+        let mut reached = vec![];
+        if difficulty == Difficulty::Challenge && elapsed_ms < FAST_CHALLENGE_MS {
+            reached.push(Achievement::FastChallenge);
+        }
+        if difficulty == Difficulty::Hard && hints == 0 {
+            reached.push(Achievement::NoHintHard);
+        }
+        if self.in_order {
+            reached.push(Achievement::UnitsInOrder);
+        }
+        reached
+            .into_iter()
+            .filter_map(|achievement| self.unlock(achievement, today))
+            .collect()
+    }
+    /// Track the cat starting to purr, returning the newly unlocked achievements
+    pub fn purred(&mut self, today: i64) -> Vec<Achievement> {
+        self.purrs += 1;
+        if self.purrs < PURRS {
+            return vec![];
+        }
+        self.unlock(Achievement::Petting, today)
+            .into_iter()
+            .collect()
+    }
+    /// Check the achievements reached according to the `stats`, returning the newly unlocked
+    /// ones
+    pub fn check(&mut self, stats: &Stats, today: i64) -> Vec<Achievement> {
+        if stats.streak.longest < MONTH_STREAK {
+            return vec![];
+        }
+        self.unlock(Achievement::MonthStreak, today)
+            .into_iter()
+            .collect()
+    }
+}
+impl Versioned for Achievements {
+    const KEY: &'static str = "achievements";
+    const VERSION: u32 = 1;
+    fn initial() -> Self {
+        Achievements {
+            unlocked: BTreeMap::new(),
+            purrs: 0,
+            // games in progress before achievements existed aren't tracked
+            in_order: false,
+        }
+    }
+}
+
+/// The [`Achievements`] together with the queue of toasts announcing new ones, provided via the
+/// context
+#[derive(Clone, Copy)]
+pub struct Tracker {
+    pub record: Signal<Achievements>,
+    toasts: Signal<Vec<Achievement>>,
+}
+impl Tracker {
+    /// Apply the `update` to the achievements on the current day and announce the newly
+    /// unlocked ones it returns
+    pub fn update(&mut self, update: impl FnOnce(&mut Achievements, i64) -> Vec<Achievement>) {
+        let unlocked = update(&mut self.record.write(), local_day());
+        if !unlocked.is_empty() {
+            self.toasts.write().extend(unlocked);
+        }
+    }
+}
+
+/// Hook that provides the [`Tracker`] to all components via the context, checking the
+/// achievements based on the statistics whenever they change
+pub fn use_achievements_provider(record: Signal<Achievements>, stats: Signal<Stats>) -> Tracker {
+    let mut tracker = use_context_provider(|| Tracker {
+        record,
+        toasts: Signal::new(vec![]),
+    });
+    use_effect(move || {
+        let stats = stats.read();
+        // only write if anything was unlocked to avoid storing the achievements on every change
+        if record.peek().clone().check(&stats, local_day()).is_empty() {
+            return;
+        }
+        tracker.update(|achievements, today| achievements.check(&stats, today));
+    });
+    tracker
+}
+
+/// Toast announcing the oldest newly unlocked achievement, until it times out or is clicked
+#[component]
+pub fn AchievementToast() -> Element {
+    let mut tracker = use_context::<Tracker>();
+    let t = use_t();
+    // remove the toast of the `achievement` if it is still shown
+    let mut dismiss = use_debounce(
+        Duration::from_millis(TOAST_DURATION),
+        move |achievement: Achievement| {
+            if tracker.toasts.peek().first() == Some(&achievement) {
+                tracker.toasts.write().remove(0);
+            }
+        },
+    );
+    let current = use_memo(move || tracker.toasts.read().first().copied());
+    // time out each toast once it is shown, restarting the timeout for the next one
+    use_effect(move || {
+        if let Some(achievement) = current() {
+            dismiss.action(achievement);
+        }
+    });
+
+    rsx!(
+        div { class: "toast-region", role: "status",
+            if let Some(achievement) = current() {
+                button {
+                    class: "toast",
+                    onclick: move |_| {
+                        tracker.toasts.write().remove(0);
+                    },
+                    span { aria_hidden: true, {achievement.emoji()} }
+                    " {t(Msg::Unlocked(&t(Msg::Achievement(achievement))))}"
+                }
+            }
+        }
+    )
+}
+
+/// Screen listing all achievements with the day they were unlocked on
+#[component]
+pub fn AchievementsScreen(on_back: Callback) -> Element {
+    let tracker = use_context::<Tracker>();
+    let locale = use_context::<Memo<Locale>>();
+    let t = use_t();
+
+    rsx!(
+        div { class: "stats",
+            table { class: "settings achievements",
+                for achievement in Achievement::iter() {
+                    {
+                        let day = tracker.record.read().unlocked.get(&achievement).copied();
+                        rsx!(
+                            tr { class: if day.is_none() { "locked" },
+                                th {
+                                    span { class: "wardrobe-item", aria_hidden: true, {achievement.emoji()} }
+                                    {t(Msg::Achievement(achievement))}
+                                }
+                                td { {t(Msg::AchievementGoal(achievement))} }
+                                td { {day.map_or(t(Msg::Locked), |day| locale().date(day))} }
+                            }
+                        )
+                    }
+                }
+            }
+            button { class: "exit-btn", onclick: move |_| on_back(()), {t(Msg::Back)} }
+        }
+    )
+}
@@ -0,0 +1,238 @@
+use std::{cell::RefCell, rc::Rc};
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{AudioContext, AudioContextState, OscillatorType};
+
+use crate::{
+    cat::{CatSprite, GameEvent},
+    stats::StreakChange,
+};
+
+// SETTINGS
+
+/// Master volumes in percent that can be chosen in the settings
+pub const VOLUMES: [u32; 5] = [0, 25, 50, 75, 100];
+/// Duration in seconds of the fade in of every tone, which avoids clicks
+const ATTACK: f64 = 0.01;
+
+/// Volume and muted categories of sounds, as part of the [`crate::settings::Settings`]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AudioSettings {
+    /// master volume in percent
+    pub volume: u32,
+    /// whether placements and completed units make sounds
+    pub effects: bool,
+    /// whether the cat meows and purrs
+    pub cat: bool,
+    /// whether a fanfare plays with the fireworks
+    pub fanfare: bool,
+}
+impl Default for AudioSettings {
+    fn default() -> Self {
+        AudioSettings {
+            volume: VOLUMES[2],
+            effects: true,
+            cat: true,
+            fanfare: true,
+        }
+    }
+}
+
+/// Sounds synthesized when the game or the cat reacts to an event
+#[derive(Clone, Copy, PartialEq)]
+pub enum Sound {
+    /// a digit was placed
+    Click,
+    /// a unit was completed
+    Chime,
+    Meow,
+    SadMeow,
+    Growl,
+    Purr,
+    /// the game was won
+    Fanfare,
+}
+impl Sound {
+    /// Whether the sound is enabled in the `settings`
+    fn enabled(&self, settings: &AudioSettings) -> bool {
+        match self {
+            Sound::Click | Sound::Chime => settings.effects,
+            Sound::Meow | Sound::SadMeow | Sound::Growl | Sound::Purr => settings.cat,
+            Sound::Fanfare => settings.fanfare,
+        }
+    }
+    /// The tones the sound is made of
+    fn tones(&self) -> Vec<Tone> {
+        use OscillatorType::*;
+        match self {
+            Sound::Click => vec![Tone::glide(Triangle, 1400., 1000., 0., 0.04, 0.3)],
+            Sound::Chime => vec![
+                Tone::new(Sine, 880., 0., 0.5, 0.4),
+                Tone::new(Sine, 1320., 0.08, 0.6, 0.3),
+            ],
+            Sound::Meow => vec![
+                Tone::glide(Triangle, 500., 900., 0., 0.15, 0.4),
+                Tone::glide(Triangle, 900., 600., 0.15, 0.25, 0.4),
+            ],
+            Sound::SadMeow => vec![
+                Tone::glide(Triangle, 700., 750., 0., 0.1, 0.4),
+                Tone::glide(Triangle, 750., 400., 0.1, 0.35, 0.4),
+            ],
+            Sound::Growl => vec![Tone::glide(Sawtooth, 110., 90., 0., 0.4, 0.25)],
+            Sound::Purr => (0..6)
+                .map(|i| Tone::glide(Sawtooth, 45., 40., i as f64 * 0.15, 0.12, 0.3))
+                .collect(),
+            Sound::Fanfare => vec![
+                Tone::new(Triangle, 523.25, 0., 0.15, 0.4),
+                Tone::new(Triangle, 659.25, 0.12, 0.15, 0.4),
+                Tone::new(Triangle, 783.99, 0.24, 0.15, 0.4),
+                Tone::new(Triangle, 1046.5, 0.36, 0.6, 0.4),
+                Tone::new(Triangle, 783.99, 0.36, 0.6, 0.25),
+                Tone::new(Triangle, 659.25, 0.36, 0.6, 0.25),
+            ],
+        }
+    }
+}
+
+/// The sounds for an `event`, which changed the sprite of the cat from `before` to `after`
+pub fn sounds(event: GameEvent, before: CatSprite, after: CatSprite) -> Vec<Sound> {
+    match event {
+        GameEvent::Placed { unit_completed, .. } if unit_completed => {
+            vec![Sound::Click, Sound::Chime]
+        }
+        GameEvent::Placed { .. } => vec![Sound::Click],
+        GameEvent::Mistake => vec![Sound::SadMeow],
+        GameEvent::Won { .. } => vec![Sound::Fanfare],
+        GameEvent::Gift(_) | GameEvent::Streak(StreakChange::Extended(_)) => vec![Sound::Meow],
+        GameEvent::Streak(StreakChange::Broken(_)) => vec![Sound::SadMeow],
+        // petting only makes sounds when the mood changes
+        GameEvent::Stroke { .. } if before != after => match after {
+            CatSprite::Purring => vec![Sound::Purr],
+            CatSprite::Annoyed => vec![Sound::Growl],
+            _ => vec![],
+        },
+        _ => vec![],
+    }
+}
+
+/// A tone gliding from one frequency to another, fading out over its duration
+struct Tone {
+    wave: OscillatorType,
+    /// frequency in Hz at the start
+    from: f64,
+    /// frequency in Hz at the end
+    to: f64,
+    /// offset in seconds from the start of the sound
+    start: f64,
+    /// duration in seconds
+    duration: f64,
+    /// peak gain at full volume
+    gain: f64,
+}
+impl Tone {
+    fn new(wave: OscillatorType, frequency: f64, start: f64, duration: f64, gain: f64) -> Self {
+        Tone::glide(wave, frequency, frequency, start, duration, gain)
+    }
+    fn glide(
+        wave: OscillatorType,
+        from: f64,
+        to: f64,
+        start: f64,
+        duration: f64,
+        gain: f64,
+    ) -> Self {
+        Tone {
+            wave,
+            from,
+            to,
+            start,
+            duration,
+            gain,
+        }
+    }
+    /// Schedule the tone on the `context`, relative to time `at` in seconds, with the
+    /// `volume` from 0 to 1
+    fn schedule(&self, context: &AudioContext, at: f64, volume: f64) -> Result<(), JsValue> {
+        let oscillator = context.create_oscillator()?;
+        let envelope = context.create_gain()?;
+        let (start, end) = (at + self.start, at + self.start + self.duration);
+        oscillator.set_type(self.wave);
+        oscillator
+            .frequency()
+            .set_value_at_time(self.from as f32, start)?;
+        oscillator
+            .frequency()
+            .exponential_ramp_to_value_at_time(self.to as f32, end)?;
+        let gain = envelope.gain();
+        gain.set_value_at_time(0., start)?;
+        gain.linear_ramp_to_value_at_time((self.gain * volume) as f32, start + ATTACK)?;
+        gain.exponential_ramp_to_value_at_time(0.0001, end)?;
+        oscillator.connect_with_audio_node(&envelope)?;
+        envelope.connect_with_audio_node(&context.destination())?;
+        oscillator.start_with_when(start)?;
+        oscillator.stop_with_when(end)?;
+        Ok(())
+    }
+}
+
+/// Synthesizer for [`Sound`]s, provided via the context. Browsers only allow audio after a
+/// gesture of the user, so the audio context is only created once the user interacts with
+/// the page, and sounds before that are dropped.
+#[derive(Clone, Default)]
+pub struct Audio {
+    context: Rc<RefCell<Option<AudioContext>>>,
+}
+impl Audio {
+    /// Create or resume the audio context, which must be called from a user gesture
+    fn unlock(&self) {
+        let mut context = self.context.borrow_mut();
+        if context.is_none() {
+            *context = AudioContext::new().ok();
+        }
+        if let Some(context) = context.as_ref() {
+            if context.state() == AudioContextState::Suspended {
+                let _ = context.resume();
+            }
+        }
+    }
+    /// Play the `sounds` at once, unless they are muted in the `settings`
+    pub fn play(&self, sounds: &[Sound], settings: &AudioSettings) {
+        let context = self.context.borrow();
+        let Some(context) = context.as_ref() else {
+            return;
+        };
+        let volume = settings.volume as f64 / 100.;
+        if volume == 0. {
+            return;
+        }
+        let now = context.current_time();
+        for sound in sounds.iter().filter(|sound| sound.enabled(settings)) {
+            for tone in sound.tones() {
+                let _ = tone.schedule(context, now, volume);
+            }
+        }
+    }
+}
+
+/// Hook that provides the [`Audio`] to all components via the context, which is unlocked by
+/// the first pointer or key press on the page
+pub fn use_audio_provider() -> Audio {
+    let audio = use_context_provider(Audio::default);
+    let unlock = audio.clone();
+    use_hook(move || {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        for event in ["pointerdown", "keydown"] {
+            let audio = unlock.clone();
+            let on_gesture = Closure::<dyn FnMut()>::new(move || audio.unlock());
+            let _ =
+                window.add_event_listener_with_callback(event, on_gesture.as_ref().unchecked_ref());
+            // the listeners live as long as the app
+            on_gesture.forget();
+        }
+    });
+    audio
+}
@@ -1,75 +1,137 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+    time::Duration,
+};
+
 use dioxus::prelude::*;
+use dioxus_sdk::utils::timing::use_interval;
+
+use crate::{
+    achievements::Tracker,
+    audio::{sounds, Audio},
+    feedback::{self, FeedbackSink},
+    fireworks::Fireworks,
+    settings::Settings,
+    shop::Item,
+    stats::StreakChange,
+    wardrobe::{Slot, Wardrobe},
+    Difficulty,
+};
 
 // SETTINGS
 
 /// Size of the cat in pixels
 pub const CAT_ASSET_PX: u32 = 300;
-/// Distance in pixels required for sufficiently petting the cat
-/// to get a reaction.
+/// Affection, i.e. distance in pixels of gentle strokes, required for sufficiently petting
+/// the cat to make it purr
 pub const CAT_PET_DIST: f64 = 500.;
+/// Speed of a stroke in pixels per millisecond from which on it counts as scratching
+pub const CAT_SCRATCH_SPEED: f64 = 1.5;
+/// Distance in pixels of scratching after which the cat is annoyed
+pub const CAT_ANNOYED_DIST: f64 = 800.;
+/// Duration in milliseconds after which half of the affection or annoyance has faded
+pub const CAT_PET_HALF_LIFE: f64 = 2_000.;
 /// Duration of the change in expression of the cat in milliseconds
 pub const CAT_EXPRESSION_DURATION: u64 = 1500;
-/// Duration of each frame of the firework animation when winning in milliseconds
-pub const CAT_FIREWORK_DURATION: u64 = 100;
-/// Number of frames of the firework animation
-pub const CAT_FIREWORK_FRAMECOUNT: usize = FIREWORK.len();
+/// Interval in milliseconds at which the mood and animation of the cat are updated
+pub const CAT_TICK: u64 = 100;
+/// Duration in milliseconds without any animation after which the cat plays an idle clip
+pub const CAT_IDLE_INTERVAL: f64 = 8_000.;
+/// Duration of inactivity of the player in milliseconds after which the cat falls asleep
+pub const CAT_SLEEP_AFTER: f64 = 45_000.;
+/// Number of recent mistakes from which on the cat is worried
+pub const CAT_WORRIED_MISTAKES: u32 = 3;
+/// Duration in milliseconds after which the cat forgets about one recent mistake
+pub const CAT_CALM_DOWN: f64 = 20_000.;
+/// Fraction of filled squares from which on the cat is excited about the upcoming win
+pub const CAT_EXCITED_PROGRESS: f64 = 0.9;
 
 /// The most crucial component of this application:
 /// a cute cat that can be pet by clicking and dragging
 /// and which is displays a happy reaction when the sudoku is filled out or won.
 pub fn Cat() -> Element {
     let cat_state = use_context::<Signal<CatState>>();
-    let mut coords: Signal<Option<(f64, f64)>> = use_signal(move || None);
-    let mut dist: Signal<f64> = use_signal(move || 0.);
-    // choose an asset for the cat depending on the state in the context
-    let cat_asset = move || match cat_state.read().state {
-        CatSprite::Normal => CAT_NORMAL,
-        CatSprite::Happy => CAT_HAPPY,
-        CatSprite::VeryHappy => CAT_VERY_HAPPY,
-        CatSprite::EasyReaction => CAT_EASY,
-        CatSprite::MediumReaction => CAT_MEDIUM,
-        CatSprite::HardReaction => CAT_HARD,
-        CatSprite::ChallengeReaction => CAT_CHALLENGE,
-        CatSprite::Fireworks(_) => CAT_FIREWORK,
+    let settings = use_context::<Signal<Settings>>();
+    let sprite = move || cat_state.read().sprite();
+    let frame = move || cat_state.read().animation.frame();
+    let wardrobe = use_context::<Signal<Wardrobe>>();
+    let on_cat = use_context::<Callback<GameEvent>>();
+    // last position and time of each pointer petting the cat, such that several fingers can
+    // pet it at once
+    let mut strokes: Signal<HashMap<i32, (f64, f64, f64)>> = use_signal(HashMap::new);
+    // track the movement of a pointer while pressed to tell strokes from scratching
+    let mut stroke = move |e: PointerEvent| {
+        let position = e.client_coordinates();
+        let now = js_sys::Date::now();
+        let Some((px, py, then)) = strokes
+            .write()
+            .insert(e.pointer_id(), (position.x, position.y, now))
+        else {
+            return;
+        };
+        let (dx, dy) = (position.x - px, position.y - py);
+        let distance = (dx * dx + dy * dy).sqrt();
+        if distance > 0. {
+            on_cat(GameEvent::Stroke {
+                distance,
+                speed: distance / (now - then).max(1.),
+                // mostly downwards, since sideways strokes ruffle the fur as well
+                along_fur: dy > dx.abs(),
+            });
+        }
+    };
+    // moods without a sprite of their own are expressed via CSS
+    let mood_class = move || match sprite() {
+        CatSprite::Sleepy => "cat-sleepy",
+        CatSprite::Excited if settings.read().animations => "cat-excited",
+        _ => "",
     };
 
     rsx!(
         div{
             // if displaying fireworks, display them over the grid
-            style: if let CatSprite::Fireworks(_) = cat_state.read().state{
+            style: if cat_state.read().celebrating {
                 "z-index: 1;"
             } else { "z-index: 0;" },
             div {
-                // display fireworks if requested
-                if let CatSprite::Fireworks(i) = cat_state.read().state{
+                // display fireworks over everything when the game is won
+                if cat_state.read().celebrating {
+                    Fireworks { intensity: cat_state.read().celebration }
+                }
+                if sprite() == CatSprite::Sleepy {
+                    span { class: "cat-zzz", aria_hidden: true, "💤" }
+                }
+                // accessories are layered over the sprite and move along with it
+                div {
+                    class: "cat {mood_class()}",
+                    style: "z-index:0; transform: translateX(-50%) {frame().map_or(\"\", |frame| frame.transform)};",
                     img {
-                        class: "cat",
-                        style: "z-index:2;",
-                        src:  FIREWORK[i]
+                        class: "cat-sprite",
+                        src: sprite().asset(),
+                        draggable: false,
+                        onpointerdown: move |e| {
+                            let position = e.client_coordinates();
+                            strokes.write().insert(e.pointer_id(), (position.x, position.y, js_sys::Date::now()));
+                        },
+                        onpointermove: move |e| {
+                            if strokes.peek().contains_key(&e.pointer_id()) {
+                                stroke(e);
+                            }
+                        },
+                        onpointerup: move |e| { strokes.write().remove(&e.pointer_id()); },
+                        onpointerleave: move |e| { strokes.write().remove(&e.pointer_id()); },
+                        onpointercancel: move |e| { strokes.write().remove(&e.pointer_id()); },
                     }
-                }
-                img {
-                    class:"cat",
-                    style: "z-index:0;",
-                    // if the cat was sufficiently pet, display love for given duration
-                    src: if *dist.read() > CAT_PET_DIST {CAT_HEARTS} else {cat_asset()},
-                    draggable: false,
-                    onpointerleave: move |_|{ *coords.write() = None; *dist.write() = 0.; },
-                    onpointerup: move |_|{ *coords.write() = None },
-                    onpointerdown: move |e|{
-                        *coords.write() = Some((e.element_coordinates().x, e.element_coordinates().y))
-                    },
-                    onpointermove: move|e|{
-                        // track the movement of the pointer while clicked to accumulate distance
-                        let mut update = false;
-                        if let Some((px, py)) = *coords.read() {
-                            update = true;
-                            let dx = e.element_coordinates().x - px;
-                            let dy = e.element_coordinates().y - py;
-                            dist += (dx*dx+dy*dy).sqrt();
-                        }
-                        if update{
-                            *coords.write() = Some((e.element_coordinates().x, e.element_coordinates().y));
+                    for accessory in wardrobe.read().equipped.values().copied() {
+                        span {
+                            class: "accessory",
+                            style: {
+                                let (left, top) = sprite().anchor(accessory.slot());
+                                format!("left: {left}%; top: {top}%;")
+                            },
+                            aria_hidden: true,
+                            {accessory.emoji()}
                         }
                     }
                 }
@@ -86,12 +148,6 @@ const CAT_OPTIONS: ImageAssetOptions = ImageAssetOptions::new()
         height: CAT_ASSET_PX,
     })
     .with_format(ImageFormat::Avif);
-const FIREWORK_OPTIONS: ImageAssetOptions = ImageAssetOptions::new()
-    .with_size(ImageSize::Manual {
-        width: CAT_ASSET_PX,
-        height: 2 * CAT_ASSET_PX,
-    })
-    .with_format(ImageFormat::Avif);
 const CAT_NORMAL: Asset = asset!("assets/images/cat/mascot.png", CAT_OPTIONS);
 const CAT_HEARTS: Asset = asset!("assets/images/cat/hearts.png", CAT_OPTIONS);
 const CAT_HAPPY: Asset = asset!("assets/images/cat/happy.png", CAT_OPTIONS);
@@ -101,18 +157,6 @@ const CAT_MEDIUM: Asset = asset!("assets/images/cat/medium.png", CAT_OPTIONS);
 const CAT_HARD: Asset = asset!("assets/images/cat/hard.png", CAT_OPTIONS);
 const CAT_CHALLENGE: Asset = asset!("assets/images/cat/challenge.png", CAT_OPTIONS);
 const CAT_FIREWORK: Asset = asset!("assets/images/cat/firework.png", CAT_OPTIONS);
-const FIREWORK: [Asset; 10] = [
-    asset!("assets/images/fireworks/0.png", FIREWORK_OPTIONS),
-    asset!("assets/images/fireworks/1.png", FIREWORK_OPTIONS),
-    asset!("assets/images/fireworks/2.png", FIREWORK_OPTIONS),
-    asset!("assets/images/fireworks/3.png", FIREWORK_OPTIONS),
-    asset!("assets/images/fireworks/4.png", FIREWORK_OPTIONS),
-    asset!("assets/images/fireworks/5.png", FIREWORK_OPTIONS),
-    asset!("assets/images/fireworks/6.png", FIREWORK_OPTIONS),
-    asset!("assets/images/fireworks/7.png", FIREWORK_OPTIONS),
-    asset!("assets/images/fireworks/8.png", FIREWORK_OPTIONS),
-    asset!("assets/images/fireworks/9.png", FIREWORK_OPTIONS),
-];
 
 // STATE DEFINITIONS
 
@@ -127,10 +171,595 @@ pub enum CatSprite {
     MediumReaction,
     HardReaction,
     ChallengeReaction,
-    Fireworks(usize),
+    Fireworks,
+    StreakExtended,
+    StreakBroken,
+    /// after a mistake
+    Disappointed,
+    /// after several recent mistakes
+    Worried,
+    /// when the grid is almost filled
+    Excited,
+    /// after a while without any action of the player
+    Sleepy,
+    /// while being pet gently
+    Purring,
+    /// after being scratched too fast
+    Annoyed,
 }
-#[derive(Clone, Copy, Default)]
-/// Holds the current reaction state of the cat
+impl CatSprite {
+    /// Image of the cat for this sprite.
+    ///
+    /// The moods and streak reactions don't have art of their own yet, so they borrow the
+    /// closest existing image until it is drawn, with [`Cat`] adding a CSS filter or animation
+    /// where that alone can't tell them apart.
+    fn asset(&self) -> Asset {
+        match self {
+            CatSprite::Normal => CAT_NORMAL,
+            CatSprite::Happy => CAT_HAPPY,
+            CatSprite::VeryHappy => CAT_VERY_HAPPY,
+            CatSprite::EasyReaction => CAT_EASY,
+            CatSprite::MediumReaction => CAT_MEDIUM,
+            CatSprite::HardReaction => CAT_HARD,
+            CatSprite::ChallengeReaction => CAT_CHALLENGE,
+            CatSprite::Fireworks => CAT_FIREWORK,
+            // borrowed art, pending sprites of their own
+            CatSprite::StreakExtended | CatSprite::Purring => CAT_HEARTS,
+            CatSprite::StreakBroken | CatSprite::Worried => CAT_CHALLENGE,
+            CatSprite::Disappointed | CatSprite::Annoyed => CAT_HARD,
+            CatSprite::Excited => CAT_MEDIUM,
+            CatSprite::Sleepy => CAT_NORMAL,
+        }
+    }
+    /// Position of accessories in the `slot` over the image of this sprite, as percentages of
+    /// the width and height of the cat from its top left corner
+    fn anchor(&self, slot: Slot) -> (u8, u8) {
+        let (head, neck) = match self {
+            // the cat leans to the left to hold the rocket
+            CatSprite::Fireworks => ((46, 8), (47, 52)),
+            CatSprite::Normal
+            | CatSprite::Happy
+            | CatSprite::VeryHappy
+            | CatSprite::EasyReaction
+            | CatSprite::MediumReaction
+            | CatSprite::HardReaction
+            | CatSprite::ChallengeReaction
+            | CatSprite::StreakExtended
+            | CatSprite::Purring
+            | CatSprite::StreakBroken
+            | CatSprite::Worried
+            | CatSprite::Disappointed
+            | CatSprite::Annoyed
+            | CatSprite::Excited
+            | CatSprite::Sleepy => ((50, 8), (50, 52)),
+        };
+        match slot {
+            Slot::Head => head,
+            Slot::Neck => neck,
+        }
+    }
+}
+
+/// Events that the cat reacts to, all of which are passed to [`CatState::handle`]
+#[derive(Clone, Copy, PartialEq)]
+pub enum GameEvent {
+    /// a difficulty was selected in the menu
+    DifficultyChosen(Difficulty),
+    GameStarted,
+    /// a correct digit was placed, filling the given fraction of the grid
+    Placed {
+        progress: f64,
+        unit_completed: bool,
+    },
+    Mistake,
+    /// the game was won, celebrated with fireworks of the given intensity
+    Won {
+        intensity: f64,
+    },
+    /// the menu is shown after a game was quit or won
+    MenuShown,
+    /// the daily streak was extended or broken
+    Streak(StreakChange),
+    /// an item bought in the shop was given to the cat
+    Gift(Item),
+    /// the cat was stroked over the given distance in pixels, at the given speed in pixels per
+    /// millisecond, along its fur (downwards) or against it
+    Stroke {
+        distance: f64,
+        speed: f64,
+        along_fur: bool,
+    },
+    /// any input of the player, which wakes the cat up
+    Activity,
+}
+
+/// A single frame of a [`Clip`]
+#[derive(PartialEq)]
+pub struct Frame {
+    /// CSS transform applied to the cat in addition to its positioning
+    pub transform: &'static str,
+}
+impl Frame {
+    const fn transform(transform: &'static str) -> Self {
+        Frame { transform }
+    }
+}
+
+/// A named animation of the cat
+#[derive(PartialEq)]
+pub struct Clip {
+    pub name: &'static str,
+    pub frames: &'static [Frame],
+    /// duration of each frame in milliseconds
+    pub frame_ms: f64,
+    /// whether the clip starts over after the last frame until it is cancelled
+    pub looping: bool,
+}
+impl Clip {
+    /// Total duration of a single run of the clip in milliseconds
+    fn duration(&self) -> f64 {
+        self.frames.len() as f64 * self.frame_ms
+    }
+}
+
+/// Cheering below the fireworks when the game is won
+pub const CHEER: Clip = Clip {
+    name: "cheer",
+    frames: &[
+        Frame::transform(""),
+        Frame::transform("translateY(-2%) rotate(-2deg)"),
+        Frame::transform("translateY(-4%)"),
+        Frame::transform("translateY(-2%) rotate(2deg)"),
+    ],
+    frame_ms: 150.,
+    looping: true,
+};
+/// Idle tail wag, swaying the cat from side to side
+pub const WAG: Clip = Clip {
+    name: "wag",
+    frames: &[
+        Frame::transform("rotate(-3deg)"),
+        Frame::transform("rotate(0deg)"),
+        Frame::transform("rotate(3deg)"),
+        Frame::transform("rotate(0deg)"),
+        Frame::transform("rotate(-3deg)"),
+        Frame::transform("rotate(0deg)"),
+    ],
+    frame_ms: 150.,
+    looping: false,
+};
+/// Idle blink, briefly squinting
+pub const BLINK: Clip = Clip {
+    name: "blink",
+    frames: &[
+        Frame::transform("scaleY(0.97)"),
+        Frame::transform("scaleY(0.94)"),
+        Frame::transform("scaleY(0.97)"),
+    ],
+    frame_ms: 100.,
+    looping: false,
+};
+/// Stretch when waking up
+pub const STRETCH: Clip = Clip {
+    name: "stretch",
+    frames: &[
+        Frame::transform("scale(1.04, 0.96)"),
+        Frame::transform("scale(1.08, 0.92)"),
+        Frame::transform("scale(1.04, 0.96)"),
+        Frame::transform("scale(0.98, 1.02)"),
+    ],
+    frame_ms: 200.,
+    looping: false,
+};
+/// Hop of joy when a unit is completed
+pub const HOP: Clip = Clip {
+    name: "hop",
+    frames: &[
+        Frame::transform("translateY(-3%)"),
+        Frame::transform("translateY(-6%)"),
+        Frame::transform("translateY(-3%)"),
+    ],
+    frame_ms: 100.,
+    looping: false,
+};
+/// Pounce on a toy
+pub const POUNCE: Clip = Clip {
+    name: "pounce",
+    frames: &[
+        Frame::transform("scale(1.05, 0.9)"),
+        Frame::transform("scale(1.1, 0.85)"),
+        Frame::transform("translateY(-8%) rotate(-4deg)"),
+        Frame::transform("translateY(-12%) rotate(-6deg)"),
+        Frame::transform("translateY(-6%) rotate(-2deg)"),
+        Frame::transform("scale(1.05, 0.95)"),
+    ],
+    frame_ms: 120.,
+    looping: false,
+};
+/// Chase a toy in a circle
+pub const SPIN: Clip = Clip {
+    name: "spin",
+    frames: &[
+        Frame::transform("rotate(-8deg)"),
+        Frame::transform("rotate(-4deg) translateX(-4%)"),
+        Frame::transform("rotate(0deg) translateY(-4%)"),
+        Frame::transform("rotate(4deg) translateX(4%)"),
+        Frame::transform("rotate(8deg)"),
+        Frame::transform("rotate(4deg) translateX(4%)"),
+        Frame::transform("rotate(0deg) translateY(-4%)"),
+        Frame::transform("rotate(-4deg) translateX(-4%)"),
+    ],
+    frame_ms: 100.,
+    looping: false,
+};
+/// Eat a treat
+pub const NOM: Clip = Clip {
+    name: "nom",
+    frames: &[
+        Frame::transform("rotate(3deg) scaleY(0.97)"),
+        Frame::transform(""),
+        Frame::transform("rotate(3deg) scaleY(0.97)"),
+        Frame::transform(""),
+        Frame::transform("rotate(3deg) scaleY(0.97)"),
+        Frame::transform(""),
+    ],
+    frame_ms: 180.,
+    looping: false,
+};
+/// Purring, a slight vibration while being pet
+pub const PURR: Clip = Clip {
+    name: "purr",
+    frames: &[
+        Frame::transform("translateX(0.4%)"),
+        Frame::transform("translateX(-0.4%)"),
+    ],
+    frame_ms: 100.,
+    looping: true,
+};
+/// Clips that the cat plays in turns while it is idle
+const IDLE_CLIPS: [&Clip; 2] = [&WAG, &BLINK];
+
+/// Clip that is currently played
+#[derive(Clone, PartialEq)]
+struct Playing {
+    clip: &'static Clip,
+    started: f64,
+    frame: usize,
+}
+
+/// Plays [`Clip`]s one after another. The animation is advanced by [`Animator::tick`], such that
+/// frames only change with the state of the cat and no timers are tied to the components.
+#[derive(Clone, Default, PartialEq)]
+pub struct Animator {
+    current: Option<Playing>,
+    queue: VecDeque<&'static Clip>,
+    /// time at which the last clip ended
+    idle_since: f64,
+    /// number of idle clips played so far, to take turns between them
+    idle_count: usize,
+}
+impl Animator {
+    /// Frame of the clip that is currently played, if any
+    pub fn frame(&self) -> Option<&'static Frame> {
+        self.current
+            .as_ref()
+            .map(|playing| &playing.clip.frames[playing.frame])
+    }
+    /// Whether the `clip` is currently played
+    pub fn is_playing(&self, clip: &Clip) -> bool {
+        self.current
+            .as_ref()
+            .is_some_and(|playing| playing.clip == clip)
+    }
+    /// Play the `clip` immediately, cancelling all current and queued clips
+    pub fn play(&mut self, clip: &'static Clip, now: f64) {
+        self.queue.clear();
+        self.current = Some(Playing {
+            clip,
+            started: now,
+            frame: 0,
+        });
+    }
+    /// Play the `clip` once the current and all previously queued clips have ended
+    pub fn enqueue(&mut self, clip: &'static Clip, now: f64) {
+        if self.current.is_none() {
+            self.play(clip, now);
+        } else {
+            self.queue.push_back(clip);
+        }
+    }
+    /// Stop all current and queued clips
+    pub fn cancel(&mut self, now: f64) {
+        self.queue.clear();
+        if self.current.take().is_some() {
+            self.idle_since = now;
+        }
+    }
+    /// Stop the `clip` if it is currently played, continuing with the queue
+    pub fn cancel_clip(&mut self, clip: &Clip, now: f64) {
+        if self.is_playing(clip) {
+            self.next(now);
+        }
+    }
+    /// Continue with the next queued clip
+    fn next(&mut self, now: f64) {
+        self.current = self.queue.pop_front().map(|clip| Playing {
+            clip,
+            started: now,
+            frame: 0,
+        });
+        self.idle_since = now;
+    }
+    /// Advance the current clip to time `now`, starting the next one if it ended. If the cat is
+    /// `idle`, an idle clip is played every once in a while.
+    fn tick(&mut self, now: f64, idle: bool) {
+        if let Some(playing) = &mut self.current {
+            let elapsed = now - playing.started;
+            if !playing.clip.looping && elapsed >= playing.clip.duration() {
+                self.next(now);
+            } else {
+                let frame = (elapsed / playing.clip.frame_ms) as usize;
+                playing.frame = frame % playing.clip.frames.len();
+            }
+        } else if idle && now - self.idle_since >= CAT_IDLE_INTERVAL {
+            self.play(IDLE_CLIPS[self.idle_count % IDLE_CLIPS.len()], now);
+            self.idle_count += 1;
+        }
+    }
+}
+
+#[derive(Clone, Default, PartialEq)]
+/// State machine of the mood of the cat, which is provided via the context as a
+/// `Signal<CatState>`. Events of the game lead to reactions, which last for a while or until the
+/// next event, after which the cat returns to a mood depending on the recent course of the game.
 pub struct CatState {
-    pub state: CatSprite,
+    /// the current reaction and the time it ends at, if it is temporary
+    reaction: Option<(CatSprite, Option<f64>)>,
+    /// time of the last event
+    last_activity: f64,
+    /// whether the cat fell asleep due to inactivity
+    asleep: bool,
+    /// number of recent mistakes
+    worry: u32,
+    /// time at which the cat forgets about one recent mistake
+    calm_at: f64,
+    /// fraction of the grid filled in the current game
+    progress: f64,
+    /// whether a game is played
+    playing: bool,
+    /// clips played on top of the current sprite
+    pub animation: Animator,
+    /// whether animations are enabled in the settings
+    animations: bool,
+    /// whether the last win is celebrated with fireworks, until the menu is shown
+    celebrating: bool,
+    /// intensity of the fireworks after the last win
+    pub celebration: f64,
+    /// recent gentle petting, which fades over time
+    affection: f64,
+    /// recent scratching, which fades over time
+    annoyance: f64,
+    /// time up to which the affection and annoyance have faded
+    felt_at: f64,
+}
+impl CatState {
+    /// The sprite expressing the current reaction or mood
+    pub fn sprite(&self) -> CatSprite {
+        if let Some((sprite, _)) = self.reaction {
+            sprite
+        } else if self.celebrating {
+            CatSprite::Fireworks
+        } else if self.asleep {
+            CatSprite::Sleepy
+        } else if self.worry >= CAT_WORRIED_MISTAKES {
+            CatSprite::Worried
+        } else if self.playing && self.progress >= CAT_EXCITED_PROGRESS {
+            CatSprite::Excited
+        } else {
+            CatSprite::Normal
+        }
+    }
+    /// React to the `event` occurring at time `now` in milliseconds
+    pub fn handle(&mut self, event: GameEvent, now: f64) {
+        self.last_activity = now;
+        if self.asleep {
+            self.asleep = false;
+            self.animate(&STRETCH, now);
+        }
+        let temporary = Some(now + CAT_EXPRESSION_DURATION as f64);
+        match event {
+            GameEvent::DifficultyChosen(difficulty) => {
+                self.reaction = Some((difficulty.cat_sprite(), None));
+            }
+            GameEvent::GameStarted => {
+                *self = CatState {
+                    last_activity: now,
+                    playing: true,
+                    animations: self.animations,
+                    ..CatState::default()
+                };
+            }
+            GameEvent::Placed {
+                progress,
+                unit_completed,
+            } => {
+                self.progress = progress;
+                let sprite = if unit_completed {
+                    CatSprite::VeryHappy
+                } else {
+                    CatSprite::Happy
+                };
+                self.reaction = Some((sprite, temporary));
+                if unit_completed {
+                    self.animate(&HOP, now);
+                }
+            }
+            GameEvent::Mistake => {
+                if self.worry == 0 {
+                    self.calm_at = now + CAT_CALM_DOWN;
+                }
+                self.worry += 1;
+                self.reaction = Some((CatSprite::Disappointed, temporary));
+            }
+            GameEvent::Won { intensity } => {
+                self.celebrating = true;
+                self.celebration = intensity;
+                self.playing = false;
+                self.worry = 0;
+                self.reaction = None;
+                if self.animations {
+                    self.animation.play(&CHEER, now);
+                }
+            }
+            GameEvent::MenuShown => {
+                self.celebrating = false;
+                self.playing = false;
+                self.worry = 0;
+                self.reaction = None;
+            }
+            GameEvent::Streak(change) => {
+                // during a game or celebration, the cat soon returns to it
+                let until = (self.playing || self.celebrating)
+                    .then_some(now + 2. * CAT_EXPRESSION_DURATION as f64);
+                self.reaction = Some((change.cat_sprite(), until));
+            }
+            GameEvent::Gift(item) => {
+                let until = now + 2. * CAT_EXPRESSION_DURATION as f64;
+                self.reaction = Some((CatSprite::VeryHappy, Some(until)));
+                if self.animations {
+                    self.animation.play(item.clip(), now);
+                }
+            }
+            GameEvent::Stroke {
+                distance,
+                speed,
+                along_fur,
+            } => self.pet(distance, speed, along_fur, now),
+            GameEvent::Activity => {}
+        }
+        self.cancel_finished_clips(now);
+    }
+    /// React to being stroked: gentle strokes along the fur build up affection until the cat
+    /// purrs, while fast scratching annoys it. Both fade over time.
+    fn pet(&mut self, distance: f64, speed: f64, along_fur: bool, now: f64) {
+        // the cat doesn't want to be distracted from celebrating
+        if self.celebrating {
+            return;
+        }
+        self.fade(now);
+        if speed >= CAT_SCRATCH_SPEED {
+            self.annoyance += distance;
+        } else if along_fur {
+            self.affection += distance;
+        } else {
+            // stroking against the fur is tolerated, but less enjoyable
+            self.affection += distance / 2.;
+        }
+        if self.annoyance >= CAT_ANNOYED_DIST {
+            self.annoyance = 0.;
+            self.affection = 0.;
+            let until = now + CAT_EXPRESSION_DURATION as f64;
+            self.reaction = Some((CatSprite::Annoyed, Some(until)));
+        } else if self.affection >= CAT_PET_DIST && self.sprite() != CatSprite::Annoyed {
+            // keep purring as long as the petting goes on
+            let until = now + CAT_EXPRESSION_DURATION as f64;
+            if self.sprite() != CatSprite::Purring {
+                self.animate(&PURR, now);
+            }
+            self.reaction = Some((CatSprite::Purring, Some(until)));
+        }
+    }
+    /// Let the affection and annoyance fade until time `now`, based on the time passed rather
+    /// than the number of ticks, since browsers throttle timers in background tabs
+    fn fade(&mut self, now: f64) {
+        let fade = 0.5f64.powf((now - self.felt_at).max(0.) / CAT_PET_HALF_LIFE);
+        for feeling in [&mut self.affection, &mut self.annoyance] {
+            *feeling *= fade;
+            // settle on zero such that the state stops changing on each tick
+            if *feeling < 1. {
+                *feeling = 0.;
+            }
+        }
+        self.felt_at = now;
+    }
+    /// Stop looping clips that belong to reactions which ended
+    fn cancel_finished_clips(&mut self, now: f64) {
+        if !self.celebrating {
+            self.animation.cancel_clip(&CHEER, now);
+        }
+        if self.sprite() != CatSprite::Purring {
+            self.animation.cancel_clip(&PURR, now);
+        }
+    }
+    /// Queue the `clip` if animations are enabled
+    fn animate(&mut self, clip: &'static Clip, now: f64) {
+        if self.animations {
+            self.animation.enqueue(clip, now);
+        }
+    }
+    /// Advance timed transitions to time `now` in milliseconds: temporary reactions end, the
+    /// cat calms down after mistakes and falls asleep when nothing happens, and clips are played
+    /// if `animations` are enabled
+    pub fn tick(&mut self, now: f64, animations: bool) {
+        if matches!(self.reaction, Some((_, Some(until))) if now >= until) {
+            self.reaction = None;
+            self.cancel_finished_clips(now);
+        }
+        if self.affection > 0. || self.annoyance > 0. {
+            self.fade(now);
+        }
+        if self.worry > 0 && now >= self.calm_at {
+            self.worry -= 1;
+            self.calm_at = now + CAT_CALM_DOWN;
+        }
+        if now - self.last_activity >= CAT_SLEEP_AFTER && !self.celebrating && !self.asleep {
+            self.asleep = true;
+            // a sleeping cat forgets its reactions
+            self.reaction = None;
+            self.animation.cancel(now);
+        }
+        self.animations = animations;
+        if animations {
+            let idle = self.sprite() == CatSprite::Normal;
+            self.animation.tick(now, idle);
+        } else {
+            self.animation.cancel(now);
+        }
+    }
+}
+
+/// Hook that provides the [`CatState`] to all components via the context and advances its
+/// timed transitions. Events are reported through the returned callback, which also plays the
+/// sounds of the reactions, passes [`feedback::Feedback`] on to the sink in the context and
+/// counts the purrs towards the achievements.
+pub fn use_cat_provider() -> Callback<GameEvent> {
+    let settings = use_context::<Signal<Settings>>();
+    let mut achievements = use_context::<Tracker>();
+    let audio = use_context::<Audio>();
+    let haptics = use_context::<Rc<dyn FeedbackSink>>();
+    let mut cat_state = use_context_provider(|| {
+        Signal::new(CatState {
+            last_activity: js_sys::Date::now(),
+            ..CatState::default()
+        })
+    });
+    let _tick = use_interval(Duration::from_millis(CAT_TICK), move || {
+        let mut next = cat_state.peek().clone();
+        next.tick(js_sys::Date::now(), settings.peek().animations);
+        // only notify the cat of actual changes, since ticks are frequent
+        if next != *cat_state.peek() {
+            cat_state.set(next);
+        }
+    });
+    let on_event = Callback::new(move |event: GameEvent| {
+        let before = cat_state.peek().sprite();
+        cat_state.write().handle(event, js_sys::Date::now());
+        let after = cat_state.peek().sprite();
+        audio.play(&sounds(event, before, after), &settings.peek().audio);
+        feedback::report(&*haptics, event);
+        if after == CatSprite::Purring && before != CatSprite::Purring {
+            achievements.update(|achievements, today| achievements.purred(today));
+        }
+    });
+    use_context_provider(|| on_event)
 }
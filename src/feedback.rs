@@ -0,0 +1,209 @@
+use std::rc::Rc;
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+use crate::{cat::GameEvent, settings::Settings};
+
+/// Moments of the game that are accompanied by feedback outside of the page, such as vibration
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Feedback {
+    Placed,
+    UnitCompleted,
+    Mistake,
+    Won,
+}
+impl Feedback {
+    /// The feedback accompanying the `event`, if any
+    pub fn from_event(event: GameEvent) -> Option<Self> {
+        match event {
+            GameEvent::Placed { unit_completed, .. } if unit_completed => {
+                Some(Feedback::UnitCompleted)
+            }
+            GameEvent::Placed { .. } => Some(Feedback::Placed),
+            GameEvent::Mistake => Some(Feedback::Mistake),
+            GameEvent::Won { .. } => Some(Feedback::Won),
+            _ => None,
+        }
+    }
+    /// Vibration pattern of alternating durations of vibration and pause in milliseconds
+    pub fn pattern(&self) -> &'static [u32] {
+        match self {
+            Feedback::Placed => &[10],
+            Feedback::UnitCompleted => &[20, 40, 20],
+            Feedback::Mistake => &[60, 40, 60],
+            Feedback::Won => &[100, 50, 100, 50, 200],
+        }
+    }
+}
+
+/// Receiver of [`Feedback`], provided via the context as a `Rc<dyn FeedbackSink>`, such that
+/// it can be replaced by one recording the feedback
+pub trait FeedbackSink {
+    fn feedback(&self, feedback: Feedback);
+}
+
+/// Pass the feedback accompanying the `event`, if any, on to the `sink`
+pub fn report(sink: &dyn FeedbackSink, event: GameEvent) {
+    if let Some(feedback) = Feedback::from_event(event) {
+        sink.feedback(feedback);
+    }
+}
+
+/// Sink that records the feedback it receives instead of passing it on, such that tests can
+/// assert on it
+#[cfg(test)]
+#[derive(Default)]
+pub struct Recorder {
+    pub received: std::cell::RefCell<Vec<Feedback>>,
+}
+#[cfg(test)]
+impl FeedbackSink for Recorder {
+    fn feedback(&self, feedback: Feedback) {
+        self.received.borrow_mut().push(feedback);
+    }
+}
+
+/// Which [`Feedback`] vibrates the device, as part of the [`crate::settings::Settings`]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HapticSettings {
+    pub placed: bool,
+    pub unit_completed: bool,
+    pub mistake: bool,
+    pub won: bool,
+}
+impl Default for HapticSettings {
+    fn default() -> Self {
+        HapticSettings {
+            placed: true,
+            unit_completed: true,
+            mistake: true,
+            won: true,
+        }
+    }
+}
+impl HapticSettings {
+    pub fn enabled(&self, feedback: Feedback) -> bool {
+        match feedback {
+            Feedback::Placed => self.placed,
+            Feedback::UnitCompleted => self.unit_completed,
+            Feedback::Mistake => self.mistake,
+            Feedback::Won => self.won,
+        }
+    }
+}
+
+/// Vibrates the device through the Vibration API of the browser, doing nothing where it is
+/// missing, such as on desktops and in Safari
+pub struct Vibration {
+    settings: Signal<Settings>,
+}
+impl FeedbackSink for Vibration {
+    fn feedback(&self, feedback: Feedback) {
+        if !self.settings.peek().haptics.enabled(feedback) {
+            return;
+        }
+        let Some(navigator) = web_sys::window().map(|window| window.navigator()) else {
+            return;
+        };
+        // calling a missing method would throw
+        if !js_sys::Reflect::has(&navigator, &"vibrate".into()).unwrap_or(false) {
+            return;
+        }
+        let pattern: js_sys::Array = feedback
+            .pattern()
+            .iter()
+            .map(|&ms| JsValue::from(ms))
+            .collect();
+        navigator.vibrate_with_pattern(&pattern);
+    }
+}
+
+/// Hook that provides the [`Vibration`] as the [`FeedbackSink`] to all components via the
+/// context
+pub fn use_feedback_provider(settings: Signal<Settings>) -> Rc<dyn FeedbackSink> {
+    use_context_provider(|| Rc::new(Vibration { settings }) as Rc<dyn FeedbackSink>)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Difficulty;
+
+    fn placed(unit_completed: bool) -> GameEvent {
+        GameEvent::Placed {
+            progress: 0.5,
+            unit_completed,
+        }
+    }
+
+    #[test]
+    fn feedback_from_event() {
+        assert_eq!(Feedback::from_event(placed(false)), Some(Feedback::Placed));
+        assert_eq!(
+            Feedback::from_event(placed(true)),
+            Some(Feedback::UnitCompleted)
+        );
+        assert_eq!(
+            Feedback::from_event(GameEvent::Mistake),
+            Some(Feedback::Mistake)
+        );
+        assert_eq!(
+            Feedback::from_event(GameEvent::Won { intensity: 1. }),
+            Some(Feedback::Won)
+        );
+        assert_eq!(Feedback::from_event(GameEvent::GameStarted), None);
+        assert_eq!(
+            Feedback::from_event(GameEvent::DifficultyChosen(Difficulty::Easy)),
+            None
+        );
+    }
+
+    #[test]
+    fn haptic_settings_enabled() {
+        let settings = HapticSettings {
+            placed: false,
+            unit_completed: true,
+            mistake: false,
+            won: true,
+        };
+        assert!(!settings.enabled(Feedback::Placed));
+        assert!(settings.enabled(Feedback::UnitCompleted));
+        assert!(!settings.enabled(Feedback::Mistake));
+        assert!(settings.enabled(Feedback::Won));
+        let default = HapticSettings::default();
+        assert!([
+            Feedback::Placed,
+            Feedback::UnitCompleted,
+            Feedback::Mistake,
+            Feedback::Won
+        ]
+        .iter()
+        .all(|feedback| default.enabled(*feedback)));
+    }
+
+    #[test]
+    fn report_records_feedback() {
+        let recorder = Recorder::default();
+        for event in [
+            GameEvent::GameStarted,
+            placed(false),
+            placed(true),
+            GameEvent::Activity,
+            GameEvent::Mistake,
+            GameEvent::Won { intensity: 2. },
+        ] {
+            report(&recorder, event);
+        }
+        assert_eq!(
+            *recorder.received.borrow(),
+            vec![
+                Feedback::Placed,
+                Feedback::UnitCompleted,
+                Feedback::Mistake,
+                Feedback::Won
+            ]
+        );
+    }
+}
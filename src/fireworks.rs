@@ -0,0 +1,258 @@
+use std::{
+    cell::{Cell, RefCell},
+    f64::consts::TAU,
+    rc::Rc,
+};
+
+use dioxus::prelude::*;
+use js_sys::Math::random;
+use wasm_bindgen::{prelude::Closure, JsCast};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+use crate::{settings::Settings, theme::Theme, Difficulty};
+
+// SETTINGS
+
+/// Number of particles per burst at intensity 1
+pub const PARTICLES_PER_BURST: f64 = 40.;
+/// Average pause between two bursts at intensity 1 in milliseconds
+pub const BURST_INTERVAL: f64 = 800.;
+/// Lifetime of a particle in milliseconds
+pub const PARTICLE_LIFETIME: f64 = 1400.;
+/// Initial speed of the particles in canvas sizes per second
+pub const BURST_SPEED: f64 = 0.35;
+/// Downward acceleration of the particles in canvas sizes per second squared
+pub const GRAVITY: f64 = 0.3;
+/// Radius of a particle relative to the canvas size
+pub const PARTICLE_RADIUS: f64 = 0.005;
+/// Time in milliseconds after the first bursts at which the fireworks are drawn as a still image
+/// if animations are disabled
+pub const STILL_AT: f64 = 300.;
+/// Expected solving time per difficulty in minutes, from which on faster games are celebrated
+/// with more intense fireworks
+const PAR_MINUTES: [f64; 4] = [5., 10., 20., 30.];
+
+/// Intensity of the fireworks for a game of the given `difficulty` won after `elapsed_ms`.
+/// Harder and faster games are celebrated with more and bigger bursts.
+pub fn intensity(difficulty: Difficulty, elapsed_ms: u64) -> f64 {
+    let (base, par) = match difficulty {
+        Difficulty::Easy => (1., PAR_MINUTES[0]),
+        Difficulty::Medium => (1.5, PAR_MINUTES[1]),
+        Difficulty::Hard => (2., PAR_MINUTES[2]),
+        Difficulty::Challenge => (3., PAR_MINUTES[3]),
+    };
+    let speed = par * 60_000. / elapsed_ms.max(1) as f64;
+    base * speed.clamp(0.5, 2.)
+}
+
+/// Procedural fireworks drawn on a canvas covering the screen, in the colours of the active
+/// [`Theme`]. Without animations, a single still frame is drawn.
+#[component]
+pub fn Fireworks(intensity: ReadOnlySignal<f64>) -> Element {
+    let settings = use_context::<Signal<Settings>>();
+    let theme = use_context::<Memo<Theme>>();
+    let animations = use_memo(move || settings.read().animations);
+    let animation = use_hook(AnimationLoop::default);
+
+    let running = animation.clone();
+    use_drop(move || running.stop());
+    use_effect(move || {
+        // restart the show with the colours of a new theme
+        let colours = theme().mark_colours();
+        let intensity = intensity();
+        animation.stop();
+        let Some((canvas, context)) = canvas() else {
+            return;
+        };
+        let mut show = Show::new(intensity, colours);
+        if !animations() {
+            show.resize(&canvas);
+            show.burst_all();
+            show.step(STILL_AT);
+            show.draw(&context);
+            return;
+        }
+        animation.start(show, canvas, context);
+    });
+
+    rsx!(canvas {
+        id: "fireworks",
+        class: "fireworks",
+        aria_hidden: true
+    })
+}
+
+/// The fireworks canvas and its drawing context, once mounted
+fn canvas() -> Option<(HtmlCanvasElement, CanvasRenderingContext2d)> {
+    let canvas = web_sys::window()?
+        .document()?
+        .get_element_by_id("fireworks")?
+        .dyn_into::<HtmlCanvasElement>()
+        .ok()?;
+    let context = canvas
+        .get_context("2d")
+        .ok()??
+        .dyn_into::<CanvasRenderingContext2d>()
+        .ok()?;
+    Some((canvas, context))
+}
+
+/// Callback of the browser for each animation frame, given its time
+type FrameCallback = Closure<dyn FnMut(f64)>;
+
+/// Loop drawing a [`Show`] on every animation frame of the browser
+#[derive(Clone, Default)]
+struct AnimationLoop {
+    /// closure called on each frame, which requests the next one
+    frame: Rc<RefCell<Option<FrameCallback>>>,
+    /// id of the pending request, to cancel it
+    request: Rc<Cell<i32>>,
+}
+impl AnimationLoop {
+    /// Draw the `show` on the `canvas` until the loop is stopped
+    fn start(&self, mut show: Show, canvas: HtmlCanvasElement, context: CanvasRenderingContext2d) {
+        let mut last = None;
+        let animation = self.clone();
+        *self.frame.borrow_mut() = Some(Closure::new(move |now: f64| {
+            show.resize(&canvas);
+            // avoid jumps after the page was in the background
+            show.step((now - last.unwrap_or(now)).min(100.));
+            last = Some(now);
+            show.draw(&context);
+            animation.request();
+        }));
+        self.request();
+    }
+    /// Request the next animation frame
+    fn request(&self) {
+        if let (Some(window), Some(frame)) = (web_sys::window(), self.frame.borrow().as_ref()) {
+            if let Ok(id) = window.request_animation_frame(frame.as_ref().unchecked_ref()) {
+                self.request.set(id);
+            }
+        }
+    }
+    /// Cancel the pending frame and drop the show
+    fn stop(&self) {
+        if let Some(window) = web_sys::window() {
+            let _ = window.cancel_animation_frame(self.request.get());
+        }
+        self.frame.borrow_mut().take();
+    }
+}
+
+/// A single spark of a burst, in canvas pixels
+struct Particle {
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+    /// remaining lifetime in milliseconds
+    life: f64,
+    colour: usize,
+}
+
+/// State of the particle system
+struct Show {
+    particles: Vec<Particle>,
+    intensity: f64,
+    colours: &'static [&'static str],
+    /// milliseconds until the next burst
+    next_burst: f64,
+    width: f64,
+    height: f64,
+}
+impl Show {
+    fn new(intensity: f64, colours: &'static [&'static str]) -> Self {
+        Show {
+            particles: Vec::new(),
+            intensity,
+            colours,
+            next_burst: 0.,
+            width: 0.,
+            height: 0.,
+        }
+    }
+    /// Reference length that all sizes and speeds are relative to
+    fn size(&self) -> f64 {
+        self.width.min(self.height)
+    }
+    /// Match the resolution of the `canvas` to its size on the screen
+    fn resize(&mut self, canvas: &HtmlCanvasElement) {
+        let ratio = web_sys::window().map_or(1., |window| window.device_pixel_ratio());
+        let width = (canvas.client_width() as f64 * ratio).round();
+        let height = (canvas.client_height() as f64 * ratio).round();
+        if width != self.width || height != self.height {
+            canvas.set_width(width as u32);
+            canvas.set_height(height as u32);
+            self.width = width;
+            self.height = height;
+        }
+    }
+    /// Launch a burst of particles at a random position in the upper part of the canvas
+    fn burst(&mut self) {
+        let x = self.width * (0.15 + 0.7 * random());
+        let y = self.height * (0.1 + 0.4 * random());
+        let colour = (random() * self.colours.len() as f64) as usize;
+        let count = (PARTICLES_PER_BURST * self.intensity.sqrt() * (0.7 + 0.6 * random())) as usize;
+        let speed = BURST_SPEED * self.size() * (0.8 + 0.4 * self.intensity.sqrt());
+        for _ in 0..count {
+            let angle = TAU * random();
+            // most sparks share the colour of their burst
+            let colour = if random() < 0.8 {
+                colour
+            } else {
+                (random() * self.colours.len() as f64) as usize
+            };
+            let speed = speed * (0.3 + 0.7 * random());
+            self.particles.push(Particle {
+                x,
+                y,
+                vx: speed * angle.cos(),
+                vy: speed * angle.sin(),
+                life: PARTICLE_LIFETIME * (0.6 + 0.4 * random()),
+                colour,
+            });
+        }
+    }
+    /// Launch as many bursts at once as are shown at a time on average
+    fn burst_all(&mut self) {
+        let count = (PARTICLE_LIFETIME / BURST_INTERVAL * self.intensity).ceil() as usize;
+        for _ in 0..count.max(1) {
+            self.burst();
+        }
+        self.next_burst = BURST_INTERVAL;
+    }
+    /// Advance the show by `dt` milliseconds
+    fn step(&mut self, dt: f64) {
+        self.next_burst -= dt;
+        while self.next_burst <= 0. {
+            self.burst();
+            self.next_burst += BURST_INTERVAL / self.intensity * (0.5 + random());
+        }
+        let seconds = dt / 1000.;
+        let gravity = GRAVITY * self.size();
+        for particle in self.particles.iter_mut() {
+            particle.x += particle.vx * seconds;
+            particle.y += particle.vy * seconds;
+            particle.vy += gravity * seconds;
+            // air resistance
+            particle.vx *= 1. - seconds;
+            particle.vy *= 1. - seconds;
+            particle.life -= dt;
+        }
+        self.particles.retain(|particle| particle.life > 0.);
+    }
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        context.set_global_alpha(1.);
+        context.clear_rect(0., 0., self.width, self.height);
+        let radius = PARTICLE_RADIUS * self.size();
+        for particle in &self.particles {
+            // sparks fade out towards the end of their life
+            context.set_global_alpha((particle.life / PARTICLE_LIFETIME * 1.5).min(1.));
+            context.set_fill_style_str(self.colours[particle.colour]);
+            context.begin_path();
+            let _ = context.arc(particle.x, particle.y, radius, 0., TAU);
+            context.fill();
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+
+// SETTINGS
+
+/// Distances in pixels that a swipe can be set to require
+pub const SWIPE_DISTANCES: [u32; 3] = [20, 40, 80];
+/// Durations in milliseconds that a long press can be set to require
+pub const LONG_PRESS_DURATIONS: [u64; 3] = [300, 500, 800];
+/// Durations in milliseconds that a two-finger tap can be set to last at most
+pub const TAP_DURATIONS: [u64; 3] = [200, 300, 500];
+/// Distance in pixels that fingers may move during a tap
+pub const TAP_SLOP: f64 = 10.;
+
+/// Thresholds of the touch gestures, as part of the [`crate::settings::Settings`]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GestureSettings {
+    /// distance in pixels a finger has to move to swipe
+    pub swipe_distance: u32,
+    /// duration in milliseconds a number button has to be held to place a note, or a square to
+    /// add it to the selection
+    pub long_press_ms: u64,
+    /// duration in milliseconds a two-finger tap may last at most to undo
+    pub tap_ms: u64,
+}
+impl Default for GestureSettings {
+    fn default() -> Self {
+        GestureSettings {
+            swipe_distance: SWIPE_DISTANCES[1],
+            long_press_ms: LONG_PRESS_DURATIONS[1],
+            tap_ms: TAP_DURATIONS[1],
+        }
+    }
+}
+
+/// Get the preset after `current`, cycling back to the first one
+pub fn next_preset<T: PartialEq + Copy>(presets: &[T], current: T) -> T {
+    let i = presets.iter().position(|p| *p == current).unwrap_or(0);
+    presets[(i + 1) % presets.len()]
+}
+
+/// Gestures recognized on the grid
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum Gesture {
+    /// a single finger tapped without moving
+    Tap,
+    /// a single finger was held without moving
+    LongPress,
+    /// a single finger moved in a direction, given as an offset in squares
+    Swipe(isize, isize),
+    /// two or more fingers tapped briefly
+    MultiTap,
+}
+
+/// Tracks the touches on an element from the first finger down to the last finger up,
+/// recognizing the [`Gesture`] they made
+#[derive(Clone, Default)]
+pub struct GestureTracker {
+    /// id and start position of the fingers currently down
+    touches: Vec<(i32, (f64, f64))>,
+    /// time the first finger went down in milliseconds
+    started_at: f64,
+    /// most fingers down at once during the gesture
+    max_touches: usize,
+    /// whether any finger moved further than a tap allows
+    moved: bool,
+}
+impl GestureTracker {
+    /// Number of fingers currently down
+    pub fn active(&self) -> usize {
+        self.touches.len()
+    }
+    /// Record a finger going down at `position` at time `now`
+    pub fn down(&mut self, id: i32, position: (f64, f64), now: f64) {
+        if self.touches.is_empty() {
+            *self = GestureTracker {
+                started_at: now,
+                ..GestureTracker::default()
+            };
+        }
+        self.touches.push((id, position));
+        self.max_touches = self.max_touches.max(self.touches.len());
+    }
+    /// Record a finger going up at `position` at time `now`, returning the gesture made once
+    /// the last finger is up
+    pub fn up(
+        &mut self,
+        id: i32,
+        position: (f64, f64),
+        now: f64,
+        settings: &GestureSettings,
+    ) -> Option<Gesture> {
+        let index = self.touches.iter().position(|(i, _)| *i == id)?;
+        let (_, (x0, y0)) = self.touches.remove(index);
+        let (dx, dy) = (position.0 - x0, position.1 - y0);
+        self.moved |= dx.hypot(dy) > TAP_SLOP;
+        if !self.touches.is_empty() {
+            return None;
+        }
+        if self.max_touches >= 2 {
+            let brief = now - self.started_at <= settings.tap_ms as f64;
+            return (brief && !self.moved).then_some(Gesture::MultiTap);
+        }
+        if dx.abs().max(dy.abs()) < settings.swipe_distance as f64 {
+            return match self.moved {
+                true => None,
+                false if now - self.started_at >= settings.long_press_ms as f64 => {
+                    Some(Gesture::LongPress)
+                }
+                false => Some(Gesture::Tap),
+            };
+        }
+        Some(if dx.abs() > dy.abs() {
+            Gesture::Swipe(dx.signum() as isize, 0)
+        } else {
+            Gesture::Swipe(0, dy.signum() as isize)
+        })
+    }
+    /// Forget a finger whose touch was interrupted, e.g. by the browser
+    pub fn cancel(&mut self, id: i32) {
+        self.touches.retain(|(i, _)| *i != id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Make a gesture with one finger from `from` to `to`, lifted after `duration` milliseconds
+    fn single(from: (f64, f64), to: (f64, f64), duration: f64) -> Option<Gesture> {
+        let mut tracker = GestureTracker::default();
+        tracker.down(1, from, 1_000.);
+        tracker.up(1, to, 1_000. + duration, &GestureSettings::default())
+    }
+
+    #[test]
+    fn tap() {
+        assert_eq!(single((50., 50.), (50., 50.), 100.), Some(Gesture::Tap));
+        // within the slop
+        assert_eq!(single((50., 50.), (55., 53.), 100.), Some(Gesture::Tap));
+        // moved, but not far enough to swipe
+        assert_eq!(single((50., 50.), (65., 50.), 100.), None);
+    }
+
+    #[test]
+    fn long_press() {
+        let held = GestureSettings::default().long_press_ms as f64;
+        assert_eq!(
+            single((50., 50.), (50., 50.), held),
+            Some(Gesture::LongPress)
+        );
+        assert_eq!(
+            single((50., 50.), (50., 50.), held - 1.),
+            Some(Gesture::Tap)
+        );
+        assert_eq!(single((50., 50.), (65., 50.), held), None);
+    }
+
+    #[test]
+    fn swipe() {
+        let far = GestureSettings::default().swipe_distance as f64;
+        assert_eq!(
+            single((50., 50.), (50. + far, 60.), 100.),
+            Some(Gesture::Swipe(1, 0))
+        );
+        assert_eq!(
+            single((50., 50.), (40., 50. - far), 100.),
+            Some(Gesture::Swipe(0, -1))
+        );
+        // however long the finger stayed down
+        assert_eq!(
+            single((50., 50.), (50. - far, 50.), 2_000.),
+            Some(Gesture::Swipe(-1, 0))
+        );
+    }
+
+    #[test]
+    fn multi_tap() {
+        let settings = GestureSettings::default();
+        let mut tracker = GestureTracker::default();
+        tracker.down(1, (50., 50.), 1_000.);
+        tracker.down(2, (90., 50.), 1_010.);
+        assert_eq!(tracker.up(1, (50., 50.), 1_100., &settings), None);
+        assert_eq!(
+            tracker.up(2, (90., 50.), 1_120., &settings),
+            Some(Gesture::MultiTap)
+        );
+        // too slow for a tap
+        tracker.down(1, (50., 50.), 2_000.);
+        tracker.down(2, (90., 50.), 2_010.);
+        tracker.up(1, (50., 50.), 3_000., &settings);
+        assert_eq!(tracker.up(2, (90., 50.), 3_000., &settings), None);
+    }
+}
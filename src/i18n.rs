@@ -0,0 +1,617 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{
+    achievements::Achievement,
+    keymap::Action,
+    settings::{InputMode, MistakeMode, Settings},
+    shop::Item,
+    storage::{backup_key, ImportError, LoadError},
+    sudoku::Technique,
+    theme::Theme,
+    wardrobe::{Accessory, Milestone},
+    Difficulty,
+};
+
+/// Language of the user interface, each with a catalogue of all [`Msg`]s
+#[derive(EnumIter, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Locale {
+    English,
+    German,
+}
+impl Locale {
+    /// BCP 47 language tag of the locale, as used for the `lang` attribute and number formatting
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::German => "de",
+        }
+    }
+    /// Name of the locale in its own language
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::German => "Deutsch",
+        }
+    }
+    /// The first locale among the languages preferred in the browser, defaulting to English
+    pub fn detect() -> Locale {
+        let languages: Vec<String> = web_sys::window()
+            .map(|window| window.navigator().languages().to_vec())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|language| language.as_string())
+            .collect();
+        languages
+            .iter()
+            .find_map(|language| {
+                let primary = language.split('-').next().unwrap_or_default();
+                Locale::iter().find(|locale| locale.tag().eq_ignore_ascii_case(primary))
+            })
+            .unwrap_or(Locale::English)
+    }
+    /// Get the locale chosen after `choice` in the settings, where `None` follows the browser
+    /// and comes before all locales
+    pub fn next_choice(choice: Option<Locale>) -> Option<Locale> {
+        match choice {
+            None => Locale::iter().next(),
+            Some(locale) => Locale::iter().skip_while(|l| *l != locale).nth(1),
+        }
+    }
+    /// Format a number with the digit grouping and decimal separator of the locale
+    pub fn number(&self, n: impl Into<f64>) -> String {
+        String::from(js_sys::Number::from(n.into()).to_locale_string(self.tag()))
+    }
+    /// Format a fraction as a rounded percentage
+    pub fn percent(&self, fraction: f64) -> String {
+        let n = self.number((100. * fraction).round());
+        match self {
+            Locale::English => format!("{n}%"),
+            Locale::German => format!("{n} %"),
+        }
+    }
+    /// Format a day since the unix epoch, as returned by [`crate::timer::local_day`], as a date
+    /// in the format of the locale
+    pub fn date(&self, day: i64) -> String {
+        let date = js_sys::Date::new(&(day as f64 * 86_400_000.).into());
+        // the day counts local midnights, which are midnights in UTC after the offset is removed
+        let options = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&options, &"timeZone".into(), &"UTC".into());
+        String::from(date.to_locale_date_string(self.tag(), &options))
+    }
+    /// Get the text of the message in this locale
+    pub fn t(&self, msg: Msg) -> String {
+        match self {
+            Locale::English => english(msg),
+            Locale::German => german(msg),
+        }
+    }
+}
+
+/// All texts shown in the user interface or read out by screen readers
+#[derive(Clone)]
+pub enum Msg<'a> {
+    // header and menu
+    Title,
+    Quit,
+    Settings,
+    Paused,
+    Pause,
+    Resume,
+    Dismiss,
+    Play,
+    Stats,
+    Shortcuts,
+    Wardrobe,
+    Shop,
+    Achievements,
+    Back,
+    LoadError(&'a LoadError),
+    Difficulty(Difficulty),
+    // game
+    Grid,
+    Erase,
+    Undo,
+    Notes,
+    InputMode(InputMode),
+    Colour(u8),
+    ClearColour,
+    /// `val` is the symbol of the digit, as chosen in the settings
+    DigitsLeft { val: &'a str, remaining: usize },
+    FilledWrong,
+    // statistics
+    Started,
+    Won,
+    WinRate,
+    Best,
+    Average,
+    Hints,
+    Mistakes,
+    Export,
+    Import,
+    FileUnreadable(&'a str),
+    ImportError(&'a ImportError),
+    StreakBest(u32),
+    // summary
+    PuzzleSolved,
+    Time,
+    Level,
+    Techniques,
+    Technique(Technique),
+    FirstBest,
+    NewBest(&'a str),
+    BehindBest { by: &'a str, best: &'a str },
+    NewGame,
+    Share,
+    ShareText {
+        difficulty: &'a str,
+        time: &'a str,
+        mistakes: u32,
+        hints: u32,
+    },
+    Copied,
+    CopyFailed,
+    BackToMenu,
+    // settings
+    Input,
+    Theme,
+    ThemeName(Theme),
+    System(&'a str),
+    Language,
+    Symbols,
+    WrongDigits,
+    MistakeMode(MistakeMode),
+    AutoNotes,
+    HighlightLines,
+    HighlightDigits,
+    Timer,
+    Animations,
+    Volume,
+    SoundEffects,
+    CatSounds,
+    Fanfare,
+    VibratePlaced,
+    VibrateUnit,
+    VibrateMistake,
+    VibrateWon,
+    On,
+    Off,
+    SwipeDistance,
+    LongPress,
+    MultiTap,
+    Pixels(&'a str),
+    Milliseconds(&'a str),
+    // wardrobe
+    Accessory(Accessory),
+    Milestone(Milestone),
+    PutOn,
+    TakeOff,
+    // shop
+    Yarn,
+    Item(Item),
+    Price(&'a str),
+    Buy,
+    // achievements
+    Achievement(Achievement),
+    AchievementGoal(Achievement),
+    Locked,
+    Unlocked(&'a str),
+    // shortcuts
+    Action(Action),
+    EnterDigit,
+    MarkColour,
+    Remove,
+    PressKey,
+    Reset,
+    Close,
+    // announcements for screen readers
+    Position { x: usize, y: usize },
+    Empty,
+    Given,
+    Wrong,
+    Candidates(&'a str),
+    Row(usize),
+    Column(usize),
+    Box(usize),
+    List { init: &'a str, last: &'a str },
+    Completed(&'a str),
+    Placed { val: &'a str, position: &'a str },
+    WrongAt { val: &'a str, position: &'a str },
+    Solved(&'a str),
+    Earned(&'a str),
+}
+
+/// English catalogue, which uses the `Display` implementations of enums for their names
+fn english(msg: Msg) -> String {
+    match msg {
+        Msg::Title => "Cadoku!".into(),
+        Msg::Quit => "Quit".into(),
+        Msg::Settings => "Settings".into(),
+        Msg::Paused => "Paused".into(),
+        Msg::Pause => "Pause".into(),
+        Msg::Resume => "Resume".into(),
+        Msg::Dismiss => "Dismiss".into(),
+        Msg::Play => "Play!".into(),
+        Msg::Stats => "Stats".into(),
+        Msg::Shortcuts => "Shortcuts".into(),
+        Msg::Wardrobe => "Wardrobe".into(),
+        Msg::Shop => "Shop".into(),
+        Msg::Achievements => "Achievements".into(),
+        Msg::Back => "Back".into(),
+        Msg::LoadError(LoadError::Corrupt { key, reason }) => format!(
+            "Saved data \"{key}\" could not be read ({reason}) and was backed up as \"{}\".",
+            backup_key(key)
+        ),
+        Msg::LoadError(LoadError::TooNew { key, version }) => format!(
+            "Saved data \"{key}\" is from a newer version (v{version}) and was backed up as \"{}\".",
+            backup_key(key)
+        ),
+        Msg::Difficulty(difficulty) => difficulty.to_string(),
+        Msg::Grid => "Sudoku".into(),
+        Msg::Erase => "Erase".into(),
+        Msg::Undo => "Undo".into(),
+        Msg::Notes => "Notes".into(),
+        Msg::InputMode(mode) => mode.to_string(),
+        Msg::Colour(colour) => format!("Colour {colour}"),
+        Msg::ClearColour => "Clear colour".into(),
+        Msg::DigitsLeft { val, remaining } => format!("{val}, {remaining} left"),
+        Msg::FilledWrong => "The grid is full, but some digits are wrong".into(),
+        Msg::Started => "Started".into(),
+        Msg::Won => "Won".into(),
+        Msg::WinRate => "Win rate".into(),
+        Msg::Best => "Best".into(),
+        Msg::Average => "Average".into(),
+        Msg::Hints => "Hints".into(),
+        Msg::Mistakes => "Mistakes".into(),
+        Msg::Export => "Export".into(),
+        Msg::Import => "Import".into(),
+        Msg::FileUnreadable(name) => format!("\"{name}\" could not be read."),
+        Msg::ImportError(ImportError::Invalid(reason)) => {
+            format!("The file could not be imported ({reason}).")
+        }
+        Msg::ImportError(ImportError::TooNew(version)) => {
+            format!("The file is from a newer version (v{version}) and could not be imported.")
+        }
+        Msg::StreakBest(longest) => format!("day streak, best: {longest}"),
+        Msg::PuzzleSolved => "Puzzle solved!".into(),
+        Msg::Time => "Time".into(),
+        Msg::Level => "Difficulty".into(),
+        Msg::Techniques => "Techniques".into(),
+        Msg::Technique(technique) => technique.to_string(),
+        Msg::FirstBest => "First win on this difficulty".into(),
+        Msg::NewBest(by) => format!("New personal best, {by} faster"),
+        Msg::BehindBest { by, best } => format!("{by} slower than your best of {best}"),
+        Msg::NewGame => "New game, same difficulty".into(),
+        Msg::Share => "Share".into(),
+        Msg::ShareText {
+            difficulty,
+            time,
+            mistakes,
+            hints,
+        } => format!(
+            "I solved a Cadoku! on {difficulty} in {time} with {mistakes} mistakes and {hints} hints."
+        ),
+        Msg::Copied => "Copied to the clipboard".into(),
+        Msg::CopyFailed => "Could not copy to the clipboard".into(),
+        Msg::BackToMenu => "Back to menu".into(),
+        Msg::Input => "Input".into(),
+        Msg::Theme => "Theme".into(),
+        Msg::ThemeName(theme) => theme.to_string(),
+        Msg::System(current) => format!("System ({current})"),
+        Msg::Language => "Language".into(),
+        Msg::Symbols => "Symbols".into(),
+        Msg::WrongDigits => "Wrong digits".into(),
+        Msg::MistakeMode(mode) => mode.to_string(),
+        Msg::AutoNotes => "Remove notes automatically".into(),
+        Msg::HighlightLines => "Highlight row and column".into(),
+        Msg::HighlightDigits => "Highlight same digits".into(),
+        Msg::Timer => "Timer".into(),
+        Msg::Animations => "Animations".into(),
+        Msg::Volume => "Volume".into(),
+        Msg::SoundEffects => "Sound effects".into(),
+        Msg::CatSounds => "Cat sounds".into(),
+        Msg::Fanfare => "Fanfare".into(),
+        Msg::VibratePlaced => "Vibrate on placement".into(),
+        Msg::VibrateUnit => "Vibrate on completed unit".into(),
+        Msg::VibrateMistake => "Vibrate on mistake".into(),
+        Msg::VibrateWon => "Vibrate on win".into(),
+        Msg::On => "On".into(),
+        Msg::Off => "Off".into(),
+        Msg::SwipeDistance => "Swipe distance".into(),
+        Msg::LongPress => "Long press for notes".into(),
+        Msg::MultiTap => "Two-finger tap to undo".into(),
+        Msg::Pixels(n) => format!("{n} px"),
+        Msg::Milliseconds(n) => format!("{n} ms"),
+        Msg::Accessory(accessory) => accessory.to_string(),
+        Msg::Milestone(milestone) => match milestone {
+            Milestone::FirstWin => "Win a puzzle".into(),
+            Milestone::Puzzles(n) => format!("Win {n} puzzles"),
+            Milestone::Streak(days) => format!("Reach a {days} day streak"),
+            Milestone::WinAt(difficulty) => format!("Win a puzzle on {difficulty}"),
+        },
+        Msg::PutOn => "Put on".into(),
+        Msg::TakeOff => "Take off".into(),
+        Msg::Yarn => "Yarn".into(),
+        Msg::Item(item) => item.to_string(),
+        Msg::Price(yarn) => format!("{yarn} yarn"),
+        Msg::Buy => "Buy".into(),
+        Msg::Achievement(achievement) => achievement.to_string(),
+        Msg::AchievementGoal(achievement) => match achievement {
+            Achievement::FastChallenge => "Win a Challenge in less than 10 minutes",
+            Achievement::NoHintHard => "Win a Hard puzzle without hints",
+            Achievement::Petting => "Make the cat purr 100 times",
+            Achievement::UnitsInOrder => "Complete all rows, columns and boxes in order",
+            Achievement::MonthStreak => "Reach a 30 day streak",
+        }
+        .into(),
+        Msg::Locked => "Locked".into(),
+        Msg::Unlocked(name) => format!("Achievement unlocked: {name}"),
+        Msg::Action(action) => action.to_string(),
+        Msg::EnterDigit => "Enter digit".into(),
+        Msg::MarkColour => "Mark colour".into(),
+        Msg::Remove => "Remove".into(),
+        Msg::PressKey => "press a key".into(),
+        Msg::Reset => "Reset".into(),
+        Msg::Close => "Close".into(),
+        Msg::Position { x, y } => format!("row {}, column {}", y + 1, x + 1),
+        Msg::Empty => "empty".into(),
+        Msg::Given => "given".into(),
+        Msg::Wrong => "wrong".into(),
+        Msg::Candidates(list) => format!("candidates {list}"),
+        Msg::Row(n) => format!("row {n}"),
+        Msg::Column(n) => format!("column {n}"),
+        Msg::Box(n) => format!("box {n}"),
+        Msg::List { init, last } => format!("{init} and {last}"),
+        Msg::Completed(units) => format!("{units} completed"),
+        Msg::Placed { val, position } => format!("{val} placed at {position}"),
+        Msg::WrongAt { val, position } => format!("{val} is wrong at {position}"),
+        Msg::Solved(time) => format!("Puzzle solved in {time}!"),
+        Msg::Earned(yarn) => format!("You earned {yarn} yarn."),
+    }
+}
+
+/// German catalogue
+fn german(msg: Msg) -> String {
+    match msg {
+        Msg::Title => "Cadoku!".into(),
+        Msg::Quit => "Beenden".into(),
+        Msg::Settings => "Einstellungen".into(),
+        Msg::Paused => "Pausiert".into(),
+        Msg::Pause => "Pause".into(),
+        Msg::Resume => "Weiter".into(),
+        Msg::Dismiss => "Ausblenden".into(),
+        Msg::Play => "Los!".into(),
+        Msg::Stats => "Statistik".into(),
+        Msg::Shortcuts => "Tastenkürzel".into(),
+        Msg::Wardrobe => "Kleiderschrank".into(),
+        Msg::Shop => "Laden".into(),
+        Msg::Achievements => "Erfolge".into(),
+        Msg::Back => "Zurück".into(),
+        Msg::LoadError(LoadError::Corrupt { key, reason }) => format!(
+            "Gespeicherte Daten \"{key}\" konnten nicht gelesen werden ({reason}) und wurden als \"{}\" gesichert.",
+            backup_key(key)
+        ),
+        Msg::LoadError(LoadError::TooNew { key, version }) => format!(
+            "Gespeicherte Daten \"{key}\" stammen aus einer neueren Version (v{version}) und wurden als \"{}\" gesichert.",
+            backup_key(key)
+        ),
+        Msg::Difficulty(difficulty) => match difficulty {
+            Difficulty::Easy => "Leicht",
+            Difficulty::Medium => "Mittel",
+            Difficulty::Hard => "Schwer",
+            Difficulty::Challenge => "Herausforderung",
+        }
+        .into(),
+        Msg::Grid => "Sudoku".into(),
+        Msg::Erase => "Löschen".into(),
+        Msg::Undo => "Rückgängig".into(),
+        Msg::Notes => "Notizen".into(),
+        Msg::InputMode(mode) => match mode {
+            InputMode::SquareFirst => "Feld zuerst",
+            InputMode::DigitFirst => "Ziffer zuerst",
+        }
+        .into(),
+        Msg::Colour(colour) => format!("Farbe {colour}"),
+        Msg::ClearColour => "Farbe entfernen".into(),
+        Msg::DigitsLeft { val, remaining } => format!("{val}, noch {remaining}"),
+        Msg::FilledWrong => "Das Gitter ist voll, aber einige Ziffern sind falsch".into(),
+        Msg::Started => "Begonnen".into(),
+        Msg::Won => "Gewonnen".into(),
+        Msg::WinRate => "Siegquote".into(),
+        Msg::Best => "Bestzeit".into(),
+        Msg::Average => "Durchschnitt".into(),
+        Msg::Hints => "Tipps".into(),
+        Msg::Mistakes => "Fehler".into(),
+        Msg::Export => "Exportieren".into(),
+        Msg::Import => "Importieren".into(),
+        Msg::FileUnreadable(name) => format!("\"{name}\" konnte nicht gelesen werden."),
+        Msg::ImportError(ImportError::Invalid(reason)) => {
+            format!("Die Datei konnte nicht importiert werden ({reason}).")
+        }
+        Msg::ImportError(ImportError::TooNew(version)) => format!(
+            "Die Datei stammt aus einer neueren Version (v{version}) und konnte nicht importiert werden."
+        ),
+        Msg::StreakBest(longest) => format!("Tage in Folge, Rekord: {longest}"),
+        Msg::PuzzleSolved => "Rätsel gelöst!".into(),
+        Msg::Time => "Zeit".into(),
+        Msg::Level => "Schwierigkeit".into(),
+        Msg::Techniques => "Techniken".into(),
+        Msg::Technique(technique) => match technique {
+            Technique::NakedSingle => "Nackte Einer",
+            Technique::HiddenSingle => "Versteckte Einer",
+            Technique::Advanced => "Fortgeschrittene Techniken",
+        }
+        .into(),
+        Msg::FirstBest => "Erster Sieg auf dieser Schwierigkeit".into(),
+        Msg::NewBest(by) => format!("Neue Bestzeit, {by} schneller"),
+        Msg::BehindBest { by, best } => format!("{by} langsamer als deine Bestzeit von {best}"),
+        Msg::NewGame => "Neues Spiel, gleiche Schwierigkeit".into(),
+        Msg::Share => "Teilen".into(),
+        Msg::ShareText {
+            difficulty,
+            time,
+            mistakes,
+            hints,
+        } => format!(
+            "Ich habe ein Cadoku! auf {difficulty} in {time} mit {mistakes} Fehlern und {hints} Tipps gelöst."
+        ),
+        Msg::Copied => "In die Zwischenablage kopiert".into(),
+        Msg::CopyFailed => "Kopieren in die Zwischenablage fehlgeschlagen".into(),
+        Msg::BackToMenu => "Zurück zum Menü".into(),
+        Msg::Input => "Eingabe".into(),
+        Msg::Theme => "Farbschema".into(),
+        Msg::ThemeName(theme) => match theme {
+            Theme::Light => "Hell",
+            Theme::Dark => "Dunkel",
+            Theme::HighContrast => "Hoher Kontrast",
+            Theme::ColourBlind => "Farbenblind-sicher",
+        }
+        .into(),
+        Msg::System(current) => format!("System ({current})"),
+        Msg::Language => "Sprache".into(),
+        Msg::Symbols => "Symbole".into(),
+        Msg::WrongDigits => "Falsche Ziffern".into(),
+        Msg::MistakeMode(mode) => match mode {
+            MistakeMode::Reject => "Ablehnen",
+            MistakeMode::Show => "Anzeigen",
+            MistakeMode::Off => "Aus",
+        }
+        .into(),
+        Msg::AutoNotes => "Notizen automatisch entfernen".into(),
+        Msg::HighlightLines => "Zeile und Spalte hervorheben".into(),
+        Msg::HighlightDigits => "Gleiche Ziffern hervorheben".into(),
+        Msg::Timer => "Stoppuhr".into(),
+        Msg::Animations => "Animationen".into(),
+        Msg::Volume => "Lautstärke".into(),
+        Msg::SoundEffects => "Soundeffekte".into(),
+        Msg::CatSounds => "Katzengeräusche".into(),
+        Msg::Fanfare => "Fanfare".into(),
+        Msg::VibratePlaced => "Vibration beim Setzen".into(),
+        Msg::VibrateUnit => "Vibration bei vollständiger Einheit".into(),
+        Msg::VibrateMistake => "Vibration bei Fehlern".into(),
+        Msg::VibrateWon => "Vibration beim Sieg".into(),
+        Msg::On => "An".into(),
+        Msg::Off => "Aus".into(),
+        Msg::SwipeDistance => "Wischdistanz".into(),
+        Msg::LongPress => "Lange drücken für Notizen".into(),
+        Msg::MultiTap => "Zwei-Finger-Tippen zum Rückgängigmachen".into(),
+        Msg::Pixels(n) => format!("{n} px"),
+        Msg::Milliseconds(n) => format!("{n} ms"),
+        Msg::Accessory(accessory) => match accessory {
+            Accessory::TopHat => "Zylinder",
+            Accessory::Bow => "Schleife",
+            Accessory::Scarf => "Schal",
+            Accessory::Crown => "Krone",
+            Accessory::BellCollar => "Glöckchenhalsband",
+            Accessory::GraduationCap => "Doktorhut",
+        }
+        .into(),
+        Msg::Milestone(milestone) => match milestone {
+            Milestone::FirstWin => "Gewinne ein Rätsel".into(),
+            Milestone::Puzzles(n) => format!("Gewinne {n} Rätsel"),
+            Milestone::Streak(days) => format!("Erreiche {days} Tage in Folge"),
+            Milestone::WinAt(difficulty) => {
+                format!("Gewinne ein Rätsel auf {}", german(Msg::Difficulty(difficulty)))
+            }
+        },
+        Msg::PutOn => "Anziehen".into(),
+        Msg::TakeOff => "Ausziehen".into(),
+        Msg::Yarn => "Wolle".into(),
+        Msg::Item(item) => match item {
+            Item::Mouse => "Spielzeugmaus",
+            Item::Feather => "Feder",
+            Item::Yarn => "Wollknäuel",
+            Item::Fish => "Fisch",
+            Item::Milk => "Milch",
+            Item::Cake => "Kuchen",
+        }
+        .into(),
+        Msg::Price(yarn) => format!("{yarn} Wolle"),
+        Msg::Buy => "Kaufen".into(),
+        Msg::Achievement(achievement) => match achievement {
+            Achievement::FastChallenge => "Blitzschnell",
+            Achievement::NoHintHard => "Ohne Hilfe",
+            Achievement::Petting => "Schmusekatze",
+            Achievement::UnitsInOrder => "Ordnung muss sein",
+            Achievement::MonthStreak => "Monatsserie",
+        }
+        .into(),
+        Msg::AchievementGoal(achievement) => match achievement {
+            Achievement::FastChallenge => "Gewinne eine Herausforderung in unter 10 Minuten",
+            Achievement::NoHintHard => "Gewinne ein schweres Rätsel ohne Tipps",
+            Achievement::Petting => "Bring die Katze 100 Mal zum Schnurren",
+            Achievement::UnitsInOrder => "Vervollständige alle Zeilen, Spalten und Blöcke der Reihe nach",
+            Achievement::MonthStreak => "Erreiche 30 Tage in Folge",
+        }
+        .into(),
+        Msg::Locked => "Gesperrt".into(),
+        Msg::Unlocked(name) => format!("Erfolg freigeschaltet: {name}"),
+        Msg::Action(action) => match action {
+            Action::MoveUp => "Nach oben",
+            Action::MoveDown => "Nach unten",
+            Action::MoveLeft => "Nach links",
+            Action::MoveRight => "Nach rechts",
+            Action::NextEmpty => "Nächstes leeres Feld",
+            Action::PreviousEmpty => "Vorheriges leeres Feld",
+            Action::RowStart => "Zeilenanfang",
+            Action::RowEnd => "Zeilenende",
+            Action::BoxUp => "Block nach oben",
+            Action::BoxDown => "Block nach unten",
+            Action::BoxLeft => "Block nach links",
+            Action::BoxRight => "Block nach rechts",
+            Action::ToggleNotes => "Notizen umschalten",
+            Action::Erase => "Löschen",
+            Action::Undo => "Rückgängig",
+            Action::ClearColour => "Farbe entfernen",
+            Action::ClearSelection => "Auswahl aufheben",
+            Action::Help => "Tastenkürzel anzeigen",
+        }
+        .into(),
+        Msg::EnterDigit => "Ziffer eingeben".into(),
+        Msg::MarkColour => "Farbe markieren".into(),
+        Msg::Remove => "Entfernen".into(),
+        Msg::PressKey => "Taste drücken".into(),
+        Msg::Reset => "Zurücksetzen".into(),
+        Msg::Close => "Schließen".into(),
+        Msg::Position { x, y } => format!("Zeile {}, Spalte {}", y + 1, x + 1),
+        Msg::Empty => "leer".into(),
+        Msg::Given => "vorgegeben".into(),
+        Msg::Wrong => "falsch".into(),
+        Msg::Candidates(list) => format!("Kandidaten {list}"),
+        Msg::Row(n) => format!("Zeile {n}"),
+        Msg::Column(n) => format!("Spalte {n}"),
+        Msg::Box(n) => format!("Block {n}"),
+        Msg::List { init, last } => format!("{init} und {last}"),
+        Msg::Completed(units) => format!("{units} vollständig"),
+        Msg::Placed { val, position } => format!("{val} gesetzt in {position}"),
+        Msg::WrongAt { val, position } => format!("{val} ist falsch in {position}"),
+        Msg::Solved(time) => format!("Rätsel gelöst in {time}!"),
+        Msg::Earned(yarn) => format!("Du hast {yarn} Wolle verdient."),
+    }
+}
+
+/// Hook that provides the current [`Locale`] to all components via the context as a
+/// `Memo<Locale>` and sets the language of the document. Unless a locale is chosen in the
+/// settings, it is detected from the languages preferred in the browser.
+pub fn use_locale_provider(settings: Signal<Settings>) -> Memo<Locale> {
+    let detected = use_hook(Locale::detect);
+    let locale = use_memo(move || settings.read().locale.unwrap_or(detected));
+    use_effect(move || {
+        let root = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.document_element());
+        if let Some(root) = root {
+            let _ = root.set_attribute("lang", locale().tag());
+        }
+    });
+    use_context_provider(|| locale)
+}
+
+/// Hook returning a function that translates a [`Msg`] into the current [`Locale`] from the
+/// context, re-rendering the component whenever the locale changes
+pub fn use_t() -> impl Fn(Msg) -> String + Copy {
+    let locale = use_context::<Memo<Locale>>();
+    move |msg| locale().t(msg)
+}
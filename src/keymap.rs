@@ -0,0 +1,293 @@
+use std::fmt::Display;
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::{
+    i18n::{use_t, Msg},
+    save::COLOURS,
+    settings::Settings,
+};
+
+/// A combination of a key and modifiers. Keys are identified by their physical location
+/// rather than the character they produce, such that bindings like WASD work independently of
+/// the keyboard layout.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyBinding {
+    /// the [`Code`] of the key as a string
+    pub code: String,
+    pub shift: bool,
+    pub ctrl: bool,
+}
+impl KeyBinding {
+    /// A binding of the given key without modifiers
+    pub fn new(code: Code) -> Self {
+        KeyBinding {
+            code: code.to_string(),
+            shift: false,
+            ctrl: false,
+        }
+    }
+    /// The same binding with Shift held
+    pub fn shift(self) -> Self {
+        KeyBinding {
+            shift: true,
+            ..self
+        }
+    }
+    /// The same binding with Ctrl (or Cmd on macOS) held
+    pub fn ctrl(self) -> Self {
+        KeyBinding { ctrl: true, ..self }
+    }
+    /// The binding pressed in a keyboard event
+    pub fn from_event(e: &KeyboardEvent) -> Self {
+        let modifiers = e.modifiers();
+        KeyBinding {
+            code: e.code().to_string(),
+            shift: modifiers.shift(),
+            ctrl: modifiers.ctrl() || modifiers.meta(),
+        }
+    }
+    /// The digit entered with this binding, if it is a digit key without modifiers.
+    /// Digits are not remappable.
+    pub fn digit(&self) -> Option<u8> {
+        if self.shift || self.ctrl {
+            return None;
+        }
+        self.digit_key()
+    }
+    /// The colour marked with this binding, if it is a digit key of a colour with Shift held.
+    /// Like digits, colours are not remappable.
+    pub fn colour(&self) -> Option<u8> {
+        if !self.shift || self.ctrl {
+            return None;
+        }
+        self.digit_key().filter(|c| *c <= COLOURS)
+    }
+    /// The digit from 1 to 9 on the key, ignoring modifiers
+    fn digit_key(&self) -> Option<u8> {
+        let digit = self
+            .code
+            .strip_prefix("Digit")
+            .or_else(|| self.code.strip_prefix("Numpad"))?;
+        digit.parse().ok().filter(|d| (1..=9).contains(d))
+    }
+    /// Whether this binding consists of a modifier key only, which can't be bound by itself
+    fn is_modifier(&self) -> bool {
+        ["Shift", "Control", "Meta", "Alt"]
+            .iter()
+            .any(|m| self.code.starts_with(m))
+    }
+}
+impl Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        let key = self
+            .code
+            .strip_prefix("Key")
+            .or_else(|| self.code.strip_prefix("Digit"))
+            .unwrap_or(&self.code);
+        match key {
+            "Slash" => write!(f, "/"),
+            key => write!(f, "{key}"),
+        }
+    }
+}
+
+/// Actions that can be triggered from the keyboard
+#[derive(EnumIter, Display, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    #[strum(to_string = "Move up")]
+    MoveUp,
+    #[strum(to_string = "Move down")]
+    MoveDown,
+    #[strum(to_string = "Move left")]
+    MoveLeft,
+    #[strum(to_string = "Move right")]
+    MoveRight,
+    #[strum(to_string = "Next empty square")]
+    NextEmpty,
+    #[strum(to_string = "Previous empty square")]
+    PreviousEmpty,
+    #[strum(to_string = "Start of row")]
+    RowStart,
+    #[strum(to_string = "End of row")]
+    RowEnd,
+    #[strum(to_string = "Box up")]
+    BoxUp,
+    #[strum(to_string = "Box down")]
+    BoxDown,
+    #[strum(to_string = "Box left")]
+    BoxLeft,
+    #[strum(to_string = "Box right")]
+    BoxRight,
+    #[strum(to_string = "Toggle notes")]
+    ToggleNotes,
+    Erase,
+    Undo,
+    #[strum(to_string = "Clear colour")]
+    ClearColour,
+    #[strum(to_string = "Clear selection")]
+    ClearSelection,
+    #[strum(to_string = "Show shortcuts")]
+    Help,
+}
+
+/// Assignment of [`KeyBinding`]s to [`Action`]s, where each binding triggers at most one action
+/// but an action can have any number of bindings
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyMap {
+    pub bindings: Vec<(KeyBinding, Action)>,
+}
+impl Default for KeyMap {
+    fn default() -> Self {
+        use Action::*;
+        use Code::*;
+        let key = KeyBinding::new;
+        KeyMap {
+            bindings: vec![
+                (key(ArrowUp), MoveUp),
+                (key(KeyW), MoveUp),
+                (key(KeyK), MoveUp),
+                (key(ArrowDown), MoveDown),
+                (key(KeyS), MoveDown),
+                (key(KeyJ), MoveDown),
+                (key(ArrowLeft), MoveLeft),
+                (key(KeyA), MoveLeft),
+                (key(KeyH), MoveLeft),
+                (key(ArrowRight), MoveRight),
+                (key(KeyD), MoveRight),
+                (key(KeyL), MoveRight),
+                // only while the grid has the focus, which Tab moves on to other controls past
+                // the last or first empty square
+                (key(Tab), NextEmpty),
+                (key(Tab).shift(), PreviousEmpty),
+                (key(Home), RowStart),
+                (key(End), RowEnd),
+                (key(ArrowUp).ctrl(), BoxUp),
+                (key(ArrowDown).ctrl(), BoxDown),
+                (key(ArrowLeft).ctrl(), BoxLeft),
+                (key(ArrowRight).ctrl(), BoxRight),
+                (key(KeyN), ToggleNotes),
+                (key(Backspace), Erase),
+                (key(Delete), Erase),
+                (key(KeyZ).ctrl(), Action::Undo),
+                (key(KeyU), Action::Undo),
+                (key(KeyC), ClearColour),
+                (key(Escape), ClearSelection),
+                (key(Slash).shift(), Action::Help),
+                (key(F1), Action::Help),
+            ],
+        }
+    }
+}
+impl KeyMap {
+    /// Get the action triggered by the given key binding
+    pub fn action(&self, key: &KeyBinding) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, action)| *action)
+    }
+    /// Get all key bindings of the given action
+    pub fn keys(&self, action: Action) -> impl Iterator<Item = &KeyBinding> {
+        self.bindings
+            .iter()
+            .filter(move |(_, a)| *a == action)
+            .map(|(k, _)| k)
+    }
+    /// Bind the key to the action, replacing any previous binding of the key
+    pub fn bind(&mut self, key: KeyBinding, action: Action) {
+        self.unbind(&key);
+        self.bindings.push((key, action));
+    }
+    /// Add the default bindings of the given `actions`, which were introduced after this
+    /// keymap was stored, except for keys that are bound already
+    pub fn add_defaults(&mut self, actions: &[Action]) {
+        for (key, action) in KeyMap::default().bindings {
+            if actions.contains(&action) && self.action(&key).is_none() {
+                self.bindings.push((key, action));
+            }
+        }
+    }
+    /// Remove the binding of the key
+    pub fn unbind(&mut self, key: &KeyBinding) {
+        self.bindings.retain(|(k, _)| k != key);
+    }
+}
+
+/// Overlay listing the keyboard shortcuts of each [`Action`], which can be remapped by
+/// removing bindings or adding new ones by pressing the desired key combination.
+#[component]
+pub fn KeyHelp(on_close: Callback) -> Element {
+    let mut settings = use_context::<Signal<Settings>>();
+    let t = use_t();
+    // the action that the next key pressed is bound to
+    let mut rebinding: Signal<Option<Action>> = use_signal(move || None);
+
+    rsx!(
+        div {
+            class: "overlay",
+            // capture keys while rebinding, without triggering any actions
+            onkeydown: move |e| {
+                let Some(action) = *rebinding.peek() else { return };
+                e.prevent_default();
+                e.stop_propagation();
+                let key = KeyBinding::from_event(&e);
+                if key.code == Code::Escape.to_string() {
+                    rebinding.set(None);
+                } else if !key.is_modifier() && key.digit().is_none() && key.colour().is_none() {
+                    settings.write().keymap.bind(key, action);
+                    rebinding.set(None);
+                }
+            },
+            h1 { {t(Msg::Shortcuts)} }
+            table {
+                class: "keymap",
+                tr {
+                    th { "1 - 9" }
+                    td { {t(Msg::EnterDigit)} }
+                }
+                tr {
+                    th { "Shift+1 - {COLOURS}" }
+                    td { {t(Msg::MarkColour)} }
+                }
+                for action in Action::iter() {
+                    tr {
+                        th { {t(Msg::Action(action))} }
+                        td {
+                            for key in settings.read().keymap.keys(action).cloned() {
+                                button {
+                                    class: "key",
+                                    title: t(Msg::Remove),
+                                    onclick: move |_| settings.write().keymap.unbind(&key),
+                                    "{key}"
+                                }
+                            }
+                            button {
+                                class: "key",
+                                onclick: move |_| rebinding.set(Some(action)),
+                                if *rebinding.read() == Some(action) { {t(Msg::PressKey)} } else { "+" }
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "stats-actions",
+                button {
+                    class: "exit-btn",
+                    onclick: move |_| settings.write().keymap = KeyMap::default(),
+                    {t(Msg::Reset)}
+                }
+                button { class: "exit-btn", onclick: move |_| on_close(()), {t(Msg::Close)} }
+            }
+        }
+    )
+}
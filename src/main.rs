@@ -1,23 +1,55 @@
 #![allow(non_snake_case)]
-use std::time::Duration;
 
 use crate::{
-    cat::{
-        Cat, CatSprite, CatState, CAT_EXPRESSION_DURATION, CAT_FIREWORK_DURATION,
-        CAT_FIREWORK_FRAMECOUNT,
+    a11y::{completed_units, position, square_label},
+    achievements::{
+        use_achievements_provider, AchievementToast, Achievements, AchievementsScreen, Tracker,
     },
-    sudoku::{generate_subtractive, Sudoku},
+    audio::use_audio_provider,
+    feedback::use_feedback_provider,
+    gestures::{Gesture, GestureTracker},
+    i18n::{use_locale_provider, use_t, Locale, Msg},
+    cat::{use_cat_provider, Cat, CatSprite, GameEvent},
+    keymap::{Action, KeyBinding, KeyHelp},
+    save::{SaveGame, COLOURS},
+    selection::Selection,
+    settings::{InputMode, MistakeMode, Settings, SettingsScreen},
+    shop::{Ledger, ShopScreen},
+    stats::{use_streak_check, Stats, StatsScreen, StreakBadge},
+    storage::{use_versioned, LoadError},
+    sudoku::generate_subtractive,
+    summary::{Summary, SummaryCard},
+    theme::use_theme_provider,
+    timer::{format_time, local_day, use_auto_pause, Clock, Timer},
+    wardrobe::{Wardrobe, WardrobeScreen},
 };
 use dioxus::prelude::*;
-use dioxus_sdk::{
-    storage::use_persistent,
-    utils::timing::{use_debounce, use_interval},
-};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{closure::Closure, JsCast};
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter};
+mod a11y;
+mod achievements;
+mod audio;
 mod cat;
 mod constants;
+mod feedback;
+mod fireworks;
+mod gestures;
+mod i18n;
+mod keymap;
+mod save;
+mod selection;
+mod settings;
+mod shop;
+mod stats;
+mod storage;
 mod sudoku;
+mod summary;
+mod symbols;
+mod theme;
+mod timer;
+mod wardrobe;
 
 // SETTINGS
 
@@ -49,33 +81,85 @@ fn main() {
 
 /// Outermost component in the tree that manages game state (with persistance) as well as menu logic, either showing a menu for difficulty selection or the [`fn::Sudoku`] component, with the [`Cat`] component below it.
 fn app() -> Element {
-    // containers and signal definitions
-    let (sudoku, solution) = (Sudoku::empty(), [0u8; 81]);
-    // use persistent storage for sudoku and solution, such that reloads don't revert progress
-    let mut sudoku = use_persistent("sudoku", move || sudoku);
-    let mut solution = use_persistent("solution", move || solution.to_vec());
+    // problems encountered while loading persistent data, shown in the menu
+    let mut load_issues = use_context_provider(|| Signal::new(Vec::<LoadError>::new()));
+    // use versioned persistent storage for the game, such that reloads don't revert progress
+    let mut game = use_versioned::<SaveGame>();
+    // statistics across all games, which are updated when games are started and won
+    let mut stats = use_versioned::<Stats>();
+    use_context_provider(|| stats);
+    // accessories of the cat, which are unlocked by milestones in the statistics
+    let mut wardrobe = use_versioned::<Wardrobe>();
+    use_context_provider(|| wardrobe);
+    use_effect(move || {
+        let stats = stats.read();
+        // only write if anything was unlocked to avoid storing the wardrobe on every change
+        let mut unlocked = wardrobe.peek().clone();
+        if unlocked.unlock(&stats) {
+            wardrobe.set(unlocked);
+        }
+    });
+    // yarn earned by winning games and spent in the shop
+    let ledger = use_versioned::<Ledger>();
+    use_context_provider(|| ledger);
+    // goals unlocked once with the day they were reached, announced in toasts
+    let mut achievements = use_achievements_provider(use_versioned::<Achievements>(), stats);
+    // preferences of the player
+    let settings = use_versioned::<Settings>();
+    use_context_provider(|| settings);
+    // colour theme, either chosen in the settings or following the system
+    use_theme_provider(settings);
+    // language of the user interface, either chosen in the settings or detected
+    use_locale_provider(settings);
+    let t = use_t();
+    // clock measuring the time spent on the current game
+    use_context_provider(|| Clock::new(game));
     // whether the sudoku grid is currently focused, which is unset if any other area is clicked
     let mut focused = use_signal(move || true);
-    // whether a game is currently played or nor. Toggles the menu and game screens respectively
-    let mut playing = use_persistent("playing", move || false);
-    use_context_provider(|| Signal::new(CatState::default()));
-    // current state (i.e. sprite) of the cat
-    let mut cat_state = use_context::<Signal<CatState>>();
+    // whether the game is paused, which hides the grid and stops the timer
+    let mut paused = use_signal(move || false);
+    use_auto_pause(paused);
+    // synthesizer for sounds, which is unlocked by the first interaction with the page
+    use_audio_provider();
+    // vibration accompanying placements, mistakes and wins
+    use_feedback_provider(settings);
+    // reports events of the game to the mood state machine of the cat
+    let on_cat = use_cat_provider();
+    // resets the daily streak once a day was missed, which the cat is sad about
+    let check_streak = use_streak_check(stats, on_cat);
     // currently selected game difficulty in the menu
     let mut difficulty = use_signal(move || None);
-    // singal saving the key code of the last pressed key and triggering input handlers
+    // the screen shown while no game is played
+    let mut screen = use_signal(move || Screen::Menu);
+    // whether the overlay listing keyboard shortcuts is shown
+    let mut show_help = use_signal(move || false);
+    // singal saving the key binding of the last pressed key and triggering input handlers
     // via `use_effect` hooks
     let mut key_pressed = use_signal(move || None);
+    // results of the game just won, shown in place of the finished grid
+    let mut summary = use_context_provider(|| Signal::new(None::<Summary>));
 
     // define behaviour when quit button is pressed
     let on_quit = Callback::new(move |_| {
-        *playing.write() = false;
+        game.write().playing = false;
+        summary.set(None);
         *difficulty.write() = None;
-        *cat_state.write() = CatState::default();
+        on_cat(GameEvent::MenuShown);
+    });
+    // start a new game of the given difficulty
+    let on_new_game = Callback::new(move |diff: Difficulty| {
+        let (new_sudoku, new_solution) = generate_subtractive(diff.hints());
+        *game.write() = SaveGame::new(new_sudoku, new_solution, diff);
+        stats.write().record_start(diff);
+        paused.set(false);
+        summary.set(None);
+        achievements.record.write().start();
+        on_cat(GameEvent::GameStarted);
+        check_streak(());
     });
     // reset if already won on load (if persistent data is solution)
     use_effect(move || {
-        if sudoku.peek().filled() {
+        if game.peek().playing && game.peek().solved() {
             on_quit(());
         }
     });
@@ -91,41 +175,124 @@ fn app() -> Element {
         div { class: "container",
             onclick: move |_| {
                 focused.set(false) ; },
-            onkeydown: move |e| {*key_pressed.write() = Some(e.code());
+            // any input wakes the cat up
+            onpointerdown: move |_| on_cat(GameEvent::Activity),
+            onkeydown: move |e| {
+                on_cat(GameEvent::Activity);
+                let key = KeyBinding::from_event(&e);
+                let action = settings.peek().keymap.action(&key);
+                // the shortcut overlay can be toggled anywhere and blocks other keys while open,
+                // where `?` is also matched by the character it produces on any layout. Tab is
+                // only handled by the grid while it has the focus, see `Sudoku`.
+                let help = action == Some(Action::Help) || e.key() == Key::Character("?".into());
+                if help || (*show_help.peek() && key.code == Code::Escape.to_string()) {
+                    e.prevent_default();
+                    show_help.toggle();
+                } else if !*show_help.peek() && key.code != Code::Tab.to_string() {
+                    // prevent default browser behaviour for keys handled in the game,
+                    // such as arrow keys scrolling the page
+                    if game.read().playing
+                        && (action.is_some() || key.digit().is_some() || key.colour().is_some())
+                    {
+                        e.prevent_default();
+                    }
+                    *key_pressed.write() = Some(key);
+                }
             },
-            // header: title and quit
+            // header: title, timer and quit
             div{
                 class: "cntr",
-                h1 { "Cadoku!" },
+                h1 { {t(Msg::Title)} },
+                div {
+                    class: "header-row",
+                    style:  if !game.read().playing {"opacity: 0; pointer-events: none;"} else {""},
+                    Timer { game, paused },
+                    button {
+                        class: "exit-btn",
+                        onclick: move |_| { if game.read().playing { on_quit.call(()); }},
+                        {t(Msg::Quit)}
+                    },
+                }
                 button {
-                    class: "exit-btn",
-                    style:  if !*playing.read() {"opacity: 0; cursor: auto;"} else {""},
-                    onclick: move |_| { if *playing.read() { on_quit.call(()); }},
-                    "Quit"
-                },
+                    class: "exit-btn settings-btn",
+                    // the game is paused while the settings hide it
+                    onclick: move |_| {
+                        if game.peek().playing {
+                            paused.set(true);
+                        }
+                        screen.set(Screen::Settings);
+                    },
+                    {t(Msg::Settings)}
+                }
             },
-            if *playing.read(){
+            if *screen.read() == Screen::Settings {
+                div { class: "btm",
+                    SettingsScreen { on_back: move |_| screen.set(Screen::Menu) }
+                }
+            } else if game.read().playing {
                 // main game
                 div { class: "btm",
                     onclick: move |e| {if !*focused.peek(){
                         focused.set(true);
                     }; e.stop_propagation();
                 },
-                    Sudoku { sudoku, solution, focused, key_pressed },
+                    if let Some(summary) = summary() {
+                        SummaryCard { summary, on_new_game, on_menu: on_quit }
+                    } else if *paused.read() {
+                        // hide the grid while paused
+                        div { class: "pause-overlay",
+                            h1 { {t(Msg::Paused)} }
+                            button {
+                                class: "menu-button",
+                                onclick: move |_| paused.set(false),
+                                {t(Msg::Resume)}
+                            }
+                        }
+                    } else {
+                        Sudoku { game, focused, key_pressed },
+                    }
                 },
-            } else{
+            } else if *screen.read() == Screen::Stats {
+                div { class: "btm",
+                    StatsScreen { on_back: move |_| screen.set(Screen::Menu) }
+                }
+            } else if *screen.read() == Screen::Shop {
+                div { class: "btm",
+                    ShopScreen { on_back: move |_| screen.set(Screen::Menu) }
+                }
+            } else if *screen.read() == Screen::Wardrobe {
+                div { class: "btm",
+                    WardrobeScreen { on_back: move |_| screen.set(Screen::Menu) }
+                }
+            } else if *screen.read() == Screen::Achievements {
+                div { class: "btm",
+                    AchievementsScreen { on_back: move |_| screen.set(Screen::Menu) }
+                }
+            } else {
                 // menu
                 div {
                     class: "btm",
+                    // report saves that could not be loaded instead of silently discarding them
+                    for issue in load_issues.read().iter() {
+                        p { class: "notice", {t(Msg::LoadError(issue))} }
+                    }
+                    if !load_issues.read().is_empty() {
+                        button {
+                            class: "exit-btn",
+                            onclick: move |_| load_issues.write().clear(),
+                            {t(Msg::Dismiss)}
+                        }
+                    }
+                    StreakBadge {}
                     for diff in Difficulty::iter(){
                         // each of the buttons for difficulty levels
                         button {
                             class: if *difficulty.read() == Some(diff) {"menu-button menu-btn-focused"} else {"menu-button"},
                             onclick: move |_|  {
                                 *difficulty.write() = Some(diff);
-                                *cat_state.write() = diff.cat_state();
+                                on_cat(GameEvent::DifficultyChosen(diff));
                             },
-                            "{diff}"
+                            {t(Msg::Difficulty(diff))}
                         }
                     }
                     // play button
@@ -133,17 +300,44 @@ fn app() -> Element {
                         class: if difficulty.read().is_some() {"menu-button"} else {"menu-button play-unfocused"},
                         onclick: move |_| async move {
                             if let Some(diff) = *difficulty.read(){
-                                let (new_sudoku, new_solution) = generate_subtractive(diff.hints());
-                                *solution.write() = new_solution.to_vec();
-                                *sudoku.write() = new_sudoku;
-                                *playing.write() = true;
-                                *cat_state.write() = CatState::default();
+                                on_new_game(diff);
                             }
                         },
-                        "Play!"
+                        {t(Msg::Play)}
+                    }
+                    div { class: "stats-actions",
+                        button {
+                            class: "exit-btn",
+                            onclick: move |_| screen.set(Screen::Stats),
+                            {t(Msg::Stats)}
+                        }
+                        button {
+                            class: "exit-btn",
+                            onclick: move |_| screen.set(Screen::Shop),
+                            {t(Msg::Shop)}
+                        }
+                        button {
+                            class: "exit-btn",
+                            onclick: move |_| screen.set(Screen::Wardrobe),
+                            {t(Msg::Wardrobe)}
+                        }
+                        button {
+                            class: "exit-btn",
+                            onclick: move |_| screen.set(Screen::Achievements),
+                            {t(Msg::Achievements)}
+                        }
+                        button {
+                            class: "exit-btn",
+                            onclick: move |_| show_help.set(true),
+                            {t(Msg::Shortcuts)}
+                        }
                     }
                 }
             }
+            if *show_help.read() {
+                KeyHelp { on_close: move |_| show_help.set(false) }
+            }
+            AchievementToast {}
             // footer: cat
             Cat { }
         }
@@ -152,74 +346,375 @@ fn app() -> Element {
 
 #[derive(PartialEq, Props, Clone)]
 struct SudokuProps {
-    sudoku: Signal<Sudoku>,
-    solution: Signal<Vec<u8>>,
+    game: Signal<SaveGame>,
     focused: Signal<bool>,
-    key_pressed: Signal<Option<Code>>,
+    key_pressed: Signal<Option<KeyBinding>>,
 }
 
 /// Main component of the game: a grid displaying the sudoku cues and providing input functionality.
 /// Squares can be selected by clicking or moving the cursors with arrows keys, numbers can be input at the
 /// cursor location via keyboard (includig the numpad) or buttons to click below the grid.
+/// Several squares can be selected by dragging the mouse, Shift-clicking or Ctrl-clicking, such that notes
+/// and colour marks are applied to all of them at once.
+/// The grid follows the ARIA grid pattern and moves are announced in a live region, such that it
+/// can be played with a screen reader.
 fn Sudoku(props: SudokuProps) -> Element {
-    let mut board = props.sudoku;
-    let mut cat_state = use_context::<Signal<CatState>>();
-    let mut cat_reset = use_debounce(Duration::from_millis(CAT_EXPRESSION_DURATION), move |_| {
-        cat_state.write().state = CatSprite::default()
+    let mut game = props.game;
+    let mut key_pressed = props.key_pressed;
+    let mut stats = use_context::<Signal<Stats>>();
+    let mut ledger = use_context::<Signal<Ledger>>();
+    let mut achievements = use_context::<Tracker>();
+    let mut clock = use_context::<Clock>();
+    let mut summary = use_context::<Signal<Option<Summary>>>();
+    let on_cat = use_context::<Callback<GameEvent>>();
+    let mut settings = use_context::<Signal<Settings>>();
+    let locale = use_context::<Memo<Locale>>();
+    let t = use_t();
+    // the selected squares, including the cursor
+    let mut selection = use_signal(Selection::default);
+    // whether squares are added to the selection by dragging the pointer across them
+    let mut dragging = use_signal(move || false);
+    // touches on the grid, recognized as taps, long presses, swipes or multi-finger taps
+    let mut gestures = use_signal(GestureTracker::default);
+    // square the first finger of the current touch went down on
+    let mut touched = use_signal(move || None::<(usize, usize)>);
+    // whether the number button just released was held long enough to place a note, such
+    // that its click is ignored
+    let mut long_pressed = use_signal(move || false);
+    // timeout of the number button currently held, after which a note is placed
+    let mut long_press: Signal<Option<i32>> = use_signal(move || None);
+    // whether digits are entered as notes instead of values
+    let mut notes_mode = use_signal(move || false);
+    // message read out by screen readers after a move
+    let mut announcement = use_signal(String::new);
+    // the digit selected on the number pad in the digit first input mode
+    let mut sticky_digit: Signal<Option<u8>> = use_signal(move || None);
+    // the digit to highlight: the one selected in the digit first input mode, or otherwise the
+    // one in the square at the cursor, if it is filled
+    let highlighted_digit = use_memo(move || {
+        if !settings.read().highlight_digits {
+            return None;
+        }
+        if settings.read().input_mode == InputMode::DigitFirst && sticky_digit().is_some() {
+            return sticky_digit();
+        }
+        let (x, y) = selection.read().cursor()?;
+        let val = game.read().sudoku.get(x, y);
+        (val > 0).then_some(val)
     });
-    let mut cursor = use_signal(move || None);
+    // whether the square at `x`,`y` is unfocused, lightly highlighted (in the same row or
+    // column as the cursor), highlighted as holding the highlighted digit, selected or
+    // strongly highlighted (at the cursor) is managed via CSS classes
+    let highlight = move |x: usize, y: usize| {
+        let cursor = selection.read().cursor();
+        if !*props.focused.read() {
+            ""
+        } else if cursor == Some((x, y)) {
+            "strongly-focused"
+        } else if selection.read().contains(x, y) {
+            "selected"
+        } else if highlighted_digit().is_some_and(|d| game.read().sudoku.get(x, y) == d) {
+            "same-digit"
+        } else if settings.read().highlight_lines
+            && cursor.is_some_and(|(x_f, y_f)| x == x_f || y == y_f)
+        {
+            "focused"
+        } else {
+            ""
+        }
+    };
+
+    // symbol displayed for the digit `val`
+    let symbol = move |val: u8| settings.read().symbols.symbol(val);
+    // classes of the square at `x`,`y` besides its highlight: its colour mark and, if enabled,
+    // whether it holds a wrong digit
+    let marks = move |x: usize, y: usize| {
+        let game = game.read();
+        let i = x + 9 * y;
+        let wrong = settings.read().mistake_mode == MistakeMode::Show && game.is_wrong(i);
+        format!("colour-{}{}", game.colours[i], if wrong { " wrong" } else { "" })
+    };
 
     // handle focus
     use_effect(move || {
         if !*props.focused.read() {
-            *cursor.write() = None;
+            selection.write().clear();
         }
     });
 
     // handle the entry of value `val` at coordinates `x`,`y`, including
-    // - checking against the solution
+    // - checking against the solution, counting mistakes
     // - updating the board state
     // - triggering an animation update of the cat
-    let mut check_entry = move |x, y, val| {
+    // - recording the statistics of a won game
+    let mut check_entry = move |x: usize, y: usize, val| {
         let i = x + 9 * y;
-        // if the input is accordance with the solution, set the square
-        if board.peek().is_zero(x, y) && val == props.solution.read()[i] {
-            let units_correct = board.peek().count_filled_units();
-            let mut updated = board.peek().clone();
-            updated.set(i, val);
-            board.set(updated);
-            // // reset focus
-            // use_effect(move ||{*cursor.write() = None;});
-            // check win condition
-            if board.peek().filled() {
-                // game has been won!
-                cat_state.write().state = CatSprite::Fireworks(0);
-                let _cat_firework_animation =
-                    use_interval(Duration::from_millis(CAT_FIREWORK_DURATION), move || {
-                        let state = cat_state.read().state;
-                        if let CatSprite::Fireworks(i) = state {
-                            cat_state.write().state =
-                                CatSprite::Fireworks((i + 1) % CAT_FIREWORK_FRAMECOUNT);
-                        }
-                    });
-                // if the animation should be interrupted at any point, insert a .cancel()
-                // here
+        let mistake_mode = settings.peek().mistake_mode;
+        // cues can't be changed, and entries only if wrong digits are accepted
+        if game.peek().givens[i]
+            || game.peek().sudoku.get(x, y) == val
+            || (mistake_mode == MistakeMode::Reject && !game.peek().sudoku.is_zero(x, y))
+        {
+            return;
+        }
+        let wrong = val != game.peek().solution[i];
+        if wrong {
+            // the input contradicts the solution
+            game.write().mistakes += 1;
+            // without feedback on mistakes, the cat doesn't react to them either
+            if mistake_mode != MistakeMode::Off {
+                on_cat(GameEvent::Mistake);
+            }
+            if mistake_mode == MistakeMode::Reject {
+                let position = position(locale(), x, y);
+                announcement.set(t(Msg::WrongAt {
+                    val: settings.peek().symbols.symbol(val),
+                    position: &position,
+                }));
                 return;
             }
-            // on successful entry, trigger a sprite change of the cat
-            let one_more_unit_done = board.peek().count_filled_units() > units_correct;
-            cat_state.write().state = if one_more_unit_done {
-                // a new unit was completed
-                // => cat is extra happy
-                CatSprite::VeryHappy
-            } else {
-                // some square was completed
-                // => cat is moderately happy
-                CatSprite::Happy
-            };
-            // return the cat to its normal state after a set duration
-            cat_reset.action(());
+        }
+        // set the square
+        let units_correct = game.peek().sudoku.count_filled_units();
+        let units_before = game.peek().sudoku.filled_units(i);
+        let auto_notes = settings.peek().auto_notes;
+        // the time so far is saved along with the move
+        clock.save();
+        game.write().place(i, val, auto_notes);
+        let position = position(locale(), x, y);
+        let mut message = t(Msg::Placed {
+            val: settings.peek().symbols.symbol(val),
+            position: &position,
+        });
+        if wrong && mistake_mode == MistakeMode::Show {
+            message.push_str(&format!(", {}", t(Msg::Wrong)));
+        }
+        let units_after = game.peek().sudoku.filled_units(i);
+        if let Some(units) = completed_units(locale(), x, y, units_before, units_after) {
+            message.push_str(&format!(". {units}"));
+        }
+        // units completed out of order rule out the achievement for this game
+        let completed = [0, 1, 2].map(|kind| units_after[kind] && !units_before[kind]);
+        let filled_before = game.peek().sudoku.filled_before(i);
+        achievements.record.write().placed(completed, filled_before);
+        // // reset focus
+        // use_effect(move ||{*cursor.write() = None;});
+        // check win condition
+        if game.peek().solved() {
+            // game has been won!
+            // stop the clock before recording the time
+            clock.tick(false);
+            let won = game.peek();
+            let time = format_time(won.elapsed_ms);
+            let mut message = format!("{message}. {}", t(Msg::Solved(&time)));
+            // compare to the best time from before this win
+            summary.set(Some(Summary::new(&won, &stats.peek())));
+            let mut streak = None;
+            if let Some(difficulty) = won.difficulty {
+                streak = stats.write().record_win(
+                    difficulty,
+                    won.elapsed_ms,
+                    won.hints,
+                    won.mistakes,
+                    local_day(),
+                );
+                // yarn is earned for every win, with bonuses for flawless play
+                let yarn = ledger.write().earn(difficulty, won.hints, won.mistakes);
+                message.push_str(&format!(" {}", t(Msg::Earned(&locale().number(yarn)))));
+                let (elapsed_ms, hints) = (won.elapsed_ms, won.hints);
+                achievements.update(|achievements, today| {
+                    achievements.won(difficulty, elapsed_ms, hints, today)
+                });
+            }
+            announcement.set(message);
+            // harder and faster wins are celebrated with more fireworks
+            let intensity = won
+                .difficulty
+                .map_or(1., |difficulty| fireworks::intensity(difficulty, won.elapsed_ms));
+            on_cat(GameEvent::Won { intensity });
+            // the cat is glad about the extended streak in the midst of the celebration
+            if let Some(change) = streak {
+                on_cat(GameEvent::Streak(change));
+            }
+            return;
+        }
+        // a full grid that isn't solved would otherwise leave the player without any hint
+        if game.peek().filled_wrong() {
+            message.push_str(&format!(". {}", t(Msg::FilledWrong)));
+        }
+        announcement.set(message);
+        // without feedback on mistakes, the cat doesn't react to entries either
+        if wrong || mistake_mode == MistakeMode::Off {
+            return;
+        }
+        // on successful entry, the cat is happy, and extra happy if a new unit was completed
+        on_cat(GameEvent::Placed {
+            progress: 1. - game.peek().sudoku.count(0) as f64 / 81.,
+            unit_completed: game.peek().sudoku.count_filled_units() > units_correct,
+        });
+    };
+
+    // number of squares that the digit `val` is still missing from, where wrong placements
+    // don't count
+    let remaining = move |val: u8| 9usize.saturating_sub(game.read().count_correct(val));
+    // a digit placed in all of its squares is deselected in the digit first input mode, since
+    // entering it again can only be a mistake
+    use_effect(move || {
+        if sticky_digit().is_some_and(|val| remaining(val) == 0) {
+            sticky_digit.set(None);
+        }
+    });
+
+    // number pad buttons of digits that are placed in all nine squares already are greyed out,
+    // the digit selected in the digit first input mode is highlighted
+    let pad_highlight = move |val: u8| {
+        if remaining(val) == 0 {
+            "num-button-done"
+        } else if sticky_digit() == Some(val) {
+            "num-button-selected"
+        } else {
+            ""
+        }
+    };
+
+    // add or remove the digit `val` as a candidate for the empty squares among `squares`
+    let mut toggle_notes = move |squares: Vec<usize>, val: u8| {
+        game.write().toggle_notes(&squares, val);
+    };
+    // remove the values and notes of the selected squares, except for cues
+    let mut erase = move || {
+        if !game.peek().solved() {
+            let squares = selection.peek().squares();
+            game.write().erase(&squares);
+        }
+    };
+    // mark the selected squares with a colour, or unmark them with colour `0`
+    let mut paint = move |colour: u8| {
+        if !game.peek().solved() {
+            let squares = selection.peek().squares();
+            game.write().paint(&squares, colour);
+        }
+    };
+    // revert the most recent move, unless the game is won already
+    let mut undo = move || {
+        if !game.peek().solved() {
+            game.write().undo();
+        }
+    };
+    // enter the digit `val` at `x`,`y`, either as a note or as a value
+    let mut enter = move |x: usize, y: usize, val: u8| {
+        if *notes_mode.peek() {
+            toggle_notes(vec![x + 9 * y], val);
+        } else {
+            check_entry(x, y, val);
+        }
+    };
+    // enter the digit `val` as a note in all selected squares, or as a value at the cursor
+    let mut enter_selected = move |val: u8| {
+        if *notes_mode.peek() {
+            toggle_notes(selection.peek().squares(), val);
+        } else if let Some((x, y)) = selection.peek().cursor() {
+            check_entry(x, y, val);
+        }
+    };
+
+    // select the square at `x`,`y` when the pointer is pressed on it, adding it to the
+    // selection with Ctrl (or Cmd) and a range with Shift, and start dragging. Touches only
+    // select once lifted, since they may turn out to be a swipe.
+    let mut press = move |e: PointerEvent, x: usize, y: usize| {
+        if e.pointer_type() == "touch" {
+            // further fingers belong to a gesture on the grid
+            if gestures.peek().active() == 0 {
+                touched.set(Some((x, y)));
+            }
+            return;
+        }
+        let modifiers = e.modifiers();
+        if modifiers.shift() {
+            selection.write().extend(x, y);
+        } else if modifiers.ctrl() || modifiers.meta() {
+            selection.write().toggle(x, y);
+        } else {
+            selection.write().select(x, y);
+        }
+        dragging.set(true);
+    };
+    // add squares to the selection while dragging across them
+    let mut hover = move |x: usize, y: usize| {
+        if *dragging.peek() {
+            selection.write().add(x, y);
+        }
+    };
+
+    // move the cursor by `dx`,`dy` squares with wraparound, or to the top left square if
+    // there is no cursor yet
+    let mut move_by = move |dx: isize, dy: isize| {
+        let (x, y) = match selection.peek().cursor() {
+            Some((x, y)) => (
+                (x as isize + dx).rem_euclid(9) as usize,
+                (y as isize + dy).rem_euclid(9) as usize,
+            ),
+            None => (0, 0),
+        };
+        selection.write().select(x, y);
+    };
+    // handle a finger lifted from the grid: a tap selects the square touched, a long press adds
+    // it to the selection, a swipe moves the cursor and a tap with two or more fingers undoes
+    // the last move
+    let lift = move |e: PointerEvent| {
+        if e.pointer_type() != "touch" {
+            return;
+        }
+        let position = e.client_coordinates();
+        let thresholds = settings.peek().gestures;
+        let gesture = gestures.write().up(
+            e.pointer_id(),
+            (position.x, position.y),
+            js_sys::Date::now(),
+            &thresholds,
+        );
+        // the gesture ends once the last finger is up
+        let square = if gestures.peek().active() == 0 {
+            touched.take()
+        } else {
+            *touched.peek()
+        };
+        match (gesture, square) {
+            (Some(Gesture::Tap), Some((x, y))) => selection.write().select(x, y),
+            (Some(Gesture::LongPress), Some((x, y))) => selection.write().toggle(x, y),
+            (Some(Gesture::Swipe(dx, dy)), _) => move_by(dx, dy),
+            (Some(Gesture::MultiTap), _) => undo(),
+            _ => {}
+        }
+    };
+    // stop waiting for the number button to be held long enough
+    let mut cancel_long_press = move || {
+        if let (Some(handle), Some(window)) = (long_press.take(), web_sys::window()) {
+            window.clear_timeout_with_handle(handle);
+        }
+    };
+    use_drop(cancel_long_press);
+    // place a note of the digit on a number button held long enough in all selected squares
+    let mut start_long_press = move |val: u8| {
+        cancel_long_press();
+        long_pressed.set(false);
+        let Some(window) = web_sys::window() else {
+            return;
         };
+        // the threshold is read on every press, such that changes in the settings apply at once
+        let threshold = settings.peek().gestures.long_press_ms as i32;
+        let on_timeout = Closure::once_into_js(move || {
+            long_press.set(None);
+            if *props.focused.peek() {
+                toggle_notes(selection.peek().squares(), val);
+                long_pressed.set(true);
+            }
+        });
+        let handle = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            on_timeout.unchecked_ref(),
+            threshold,
+        );
+        long_press.set(handle.ok());
     };
 
     // handle keyboard inputs
@@ -227,85 +722,187 @@ fn Sudoku(props: SudokuProps) -> Element {
         // keypress should be the ONLY dependency here, use `peek` to prevent
         // subscriptions to anything but the `key_pressed` prop, which should
         // trigger re-runs of this closure
-        let keypress = *props.key_pressed.read();
-        if *props.focused.peek() {
-            let cursor_cur = *cursor.peek();
-            if let Some((x, y)) = cursor_cur {
-                if let Some(code) = keypress {
-                    match code {
-                        // check for numbers entered
-                        Code::Digit1 | Code::Numpad1 => check_entry(x, y, 1u8),
-                        Code::Digit2 | Code::Numpad2 => check_entry(x, y, 2u8),
-                        Code::Digit3 | Code::Numpad3 => check_entry(x, y, 3u8),
-                        Code::Digit4 | Code::Numpad4 => check_entry(x, y, 4u8),
-                        Code::Digit5 | Code::Numpad5 => check_entry(x, y, 5u8),
-                        Code::Digit6 | Code::Numpad6 => check_entry(x, y, 6u8),
-                        Code::Digit7 | Code::Numpad7 => check_entry(x, y, 7u8),
-                        Code::Digit8 | Code::Numpad8 => check_entry(x, y, 8u8),
-                        Code::Digit9 | Code::Numpad9 => check_entry(x, y, 9u8),
-                        // check for cursor movement
-                        Code::ArrowDown => cursor.set(Some((x, (y + 1) % 9))),
-                        Code::ArrowLeft => cursor.set(Some(((x + 8) % 9, y))),
-                        Code::ArrowRight => cursor.set(Some(((x + 1) % 9, y))),
-                        Code::ArrowUp => cursor.set(Some((x, (y + 8) % 9))),
-                        _ => {}
-                    };
-                }
+        let Some(key) = props.key_pressed.read().clone() else {
+            return;
+        };
+        if !*props.focused.peek() {
+            return;
+        }
+        let cursor_cur = selection.peek().cursor();
+        // check for numbers and colours entered
+        if let Some(val) = key.digit() {
+            return enter_selected(val);
+        }
+        if let Some(colour) = key.colour() {
+            return paint(colour);
+        }
+        let Some(action) = settings.peek().keymap.action(&key) else {
+            return;
+        };
+        // without a cursor, any movement starts at the top left square
+        let (x, y) = cursor_cur.unwrap_or((0, 0));
+        let mut move_to = move |to: (usize, usize)| {
+            let (x, y) = if cursor_cur.is_some() { to } else { (0, 0) };
+            selection.write().select(x, y);
+        };
+        let mut move_to_empty = move |forward: bool| {
+            let from = cursor_cur.map(|(x, y)| x + 9 * y);
+            if let Some(i) = game.peek().sudoku.next_empty(from, forward) {
+                selection.write().select(i % 9, i / 9);
             }
+        };
+        match action {
+            // check for cursor movement
+            Action::MoveDown => move_to((x, (y + 1) % 9)),
+            Action::MoveLeft => move_to(((x + 8) % 9, y)),
+            Action::MoveRight => move_to(((x + 1) % 9, y)),
+            Action::MoveUp => move_to((x, (y + 8) % 9)),
+            Action::BoxDown => move_to((x, (y + 3) % 9)),
+            Action::BoxLeft => move_to(((x + 6) % 9, y)),
+            Action::BoxRight => move_to(((x + 3) % 9, y)),
+            Action::BoxUp => move_to((x, (y + 6) % 9)),
+            Action::RowStart => move_to((0, y)),
+            Action::RowEnd => move_to((8, y)),
+            Action::NextEmpty => move_to_empty(true),
+            Action::PreviousEmpty => move_to_empty(false),
+            // check for other actions
+            Action::ToggleNotes => notes_mode.toggle(),
+            Action::Undo => undo(),
+            Action::Erase => erase(),
+            Action::ClearColour => paint(0),
+            Action::ClearSelection => selection.write().clear(),
+            // handled by the app, since it is available outside of the game too
+            Action::Help => {}
         }
     });
 
     rsx! (
         div { class: "btm",
+            // stop dragging once the pointer is released or leaves the grid
+            onpointerup: move |_| dragging.set(false),
         div { class: "grid",
+            onpointerleave: move |_| dragging.set(false),
+            // track touches for gestures
+            onpointerdown: move |e| {
+                if e.pointer_type() == "touch" {
+                    let position = e.client_coordinates();
+                    gestures.write().down(e.pointer_id(), (position.x, position.y), js_sys::Date::now());
+                }
+            },
+            onpointerup: lift,
+            onpointercancel: move |e| gestures.write().cancel(e.pointer_id()),
+            // the squares are laid out by box, while assistive technologies expect rows of
+            // squares: the rows own the squares by id and the cursor is the active descendant
+            role: "grid",
+            aria_label: t(Msg::Grid),
+            aria_rowcount: 9,
+            aria_colcount: 9,
+            tabindex: 0,
+            // Tab jumps between the empty squares, until it moves the focus on to other controls
+            // past the last or first one
+            onkeydown: move |e| {
+                let key = KeyBinding::from_event(&e);
+                let forward = match settings.peek().keymap.action(&key) {
+                    Some(Action::NextEmpty) => true,
+                    Some(Action::PreviousEmpty) => false,
+                    _ => return,
+                };
+                if key.code != Code::Tab.to_string() {
+                    return;
+                }
+                let from = selection.peek().cursor().map(|(x, y)| x + 9 * y);
+                let next = game.peek().sudoku.next_empty(from, forward);
+                let within = match (next, from) {
+                    (Some(next), Some(from)) => next != from && (next > from) == forward,
+                    (next, None) => next.is_some(),
+                    (None, _) => false,
+                };
+                if within {
+                    e.prevent_default();
+                    key_pressed.set(Some(key));
+                }
+            },
+            aria_activedescendant: selection.read().cursor().map(|(x, y)| format!("square-{}", x + 9 * y)),
+            for row in 0..9 {
+                div {
+                    class: "visually-hidden",
+                    role: "row",
+                    aria_rowindex: row + 1,
+                    aria_owns: (0..9).map(|col| format!("square-{}", col + 9 * row)).collect::<Vec<_>>().join(" "),
+                }
+            }
             for gy in 0..3 {
             for gx in 0..3 {
                 div {
                     class: "subgrid" ,
+                    role: "none",
                     for y in 0..3 {
                     for x in 0..3 {
-                        // extra div to hold debug hints
+                        // extra div to hold debug hints, which is the grid cell of the square
                         div {  style: "position: relative;",
-                            if board.read().is_zero(3*gx+x,3*gy+y){
+                            id: "square-{3*gx+x + 9*(3*gy+y)}",
+                            role: "gridcell",
+                            aria_colindex: 3*gx+x + 1,
+                            aria_selected: selection.read().contains(3*gx+x, 3*gy+y),
+                            aria_readonly: game.read().givens[3*gx+x + 9*(3*gy+y)],
+                            aria_label: square_label(locale(), settings.read().symbols, &game.read(), 3*gx+x, 3*gy+y, settings.read().mistake_mode == MistakeMode::Show),
+                            if game.read().sudoku.is_zero(3*gx+x,3*gy+y){
                                 // if the square is empty, show an input field
                                 button {
-                                    // whether the square is unfocused
-                                    // lightly highlighted (in same row or column as cursor)
-                                    // or strongly highlighted (at the cursor)
-                                    // is managed via CSS classes
-                                    class: if let Some((x_f, y_f)) = *cursor.read() {
-                                        if *props.focused.read() && ((3*gx+x) == x_f && (3*gy+y) == y_f) {
-                                            "emptysquare strongly-focused"
-                                        } else if *props.focused.read() && ((3*gx+x) == x_f || (3*gy+y) == y_f) {
-                                            "emptysquare focused"
-                                        } else {
-                                            "emptysquare"
-                                        }
-                                    } else {
-                                        "emptysquare"
-                                    },
+                                    // the grid cell is labelled instead and focused via the grid
+                                    tabindex: -1,
+                                    aria_hidden: true,
+                                    class: "emptysquare {highlight(3*gx+x, 3*gy+y)} {marks(3*gx+x, 3*gy+y)}",
                                     // prevent default HTML input event, since keystrokes
                                     // are already captured in a parent div and handled by a
                                     // use_effect hook on the `key_pressed` prop
                                     onkeydown: move |e| {e.prevent_default();},
-                                    // focus the targeted square on click
-                                    onfocusin: move |_|{ cursor.set(Some((3*gx+x,3*gy+y)));},
+                                    // select the targeted square on press or while dragging
+                                    onpointerdown: move |e| press(e, 3*gx+x, 3*gy+y),
+                                    onpointerenter: move |_| hover(3*gx+x, 3*gy+y),
+                                    // in the digit first input mode, enter the selected digit,
+                                    // unless the click changed a selection of several squares
+                                    onclick: move |e| {
+                                        let modifiers = e.modifiers();
+                                        let extending = modifiers.shift() || modifiers.ctrl() || modifiers.meta();
+                                        if settings.peek().input_mode == InputMode::DigitFirst && !extending {
+                                            let selected = *sticky_digit.peek();
+                                            if let Some(val) = selected.filter(|val| remaining(*val) > 0) {
+                                                enter(3*gx+x, 3*gy+y, val);
+                                            }
+                                        }
+                                    },
+                                    // candidates noted by the player
+                                    div { class: "notes",
+                                        for val in 1..=9u8 {
+                                            span {
+                                                if game.read().has_note(3*gx+x + 9*(3*gy+y), val) { {symbol(val)} }
+                                            }
+                                        }
+                                    }
                                 }
                             } else {
                                 // if the square is not empty, show the number in it
                                 span {
-                                    class: if let Some((x_f, y_f)) = *cursor.read(){
-                                        if *props.focused.read() && ((3*gx+x) == x_f || (3*gy+y) == y_f) {
-                                        "square focused"
-                                        } else {"square"}
-                                    } else {"square"},
-                                    "{props.solution.read()[3*gx+x + 9*(3*gy+y)]}" },
+                                    aria_hidden: true,
+                                    // entries of the player are styled differently from cues
+                                    class: if game.read().givens[3*gx+x + 9*(3*gy+y)] {
+                                        "square {highlight(3*gx+x, 3*gy+y)} {marks(3*gx+x, 3*gy+y)}"
+                                    } else {
+                                        "square entry {highlight(3*gx+x, 3*gy+y)} {marks(3*gx+x, 3*gy+y)}"
+                                    },
+                                    // move the cursor to the square on press, highlighting
+                                    // all squares with the same digit
+                                    onpointerdown: move |e| press(e, 3*gx+x, 3*gy+y),
+                                    onpointerenter: move |_| hover(3*gx+x, 3*gy+y),
+                                    {symbol(game.read().sudoku.get(3*gx+x, 3*gy+y))} },
                             },
                             // for debugging  show the solution in the dom,
                             // but don't render it visibly
                             span {
                                 class: "secret-hacker-hint",
-                                "{props.solution.read()[3*gx+x + 9*(3*gy+y)]}",
+                                aria_hidden: true,
+                                "{game.read().solution[3*gx+x + 9*(3*gy+y)]}",
                             },
                         }
                     }
@@ -314,31 +911,118 @@ fn Sudoku(props: SudokuProps) -> Element {
             }
             }
         },
-        // alternative input: buttons that enter at the currently focused cell, if applicable
+        // announcements of moves for screen readers
+        div { class: "visually-hidden", role: "status", aria_live: "polite", "{announcement}" }
+        if game.read().filled_wrong() {
+            p { class: "notice", {t(Msg::FilledWrong)} }
+        }
+        // alternative input: buttons that enter at the currently focused cell, if applicable,
+        // or select the digit to enter in the digit first input mode
         // this enables playing with mouse or on a touch device
         div {
             class: "button-container",
             for val in 1..=9{
                 button {
-                    class: "num-button",
+                    class: "num-button {pad_highlight(val)}",
+                    disabled: remaining(val) == 0,
+                    aria_label: t(Msg::DigitsLeft { val: symbol(val), remaining: remaining(val) }),
+                    aria_pressed: sticky_digit() == Some(val),
+                    // holding the button places a note instead
+                    onpointerdown: move |_| start_long_press(val),
+                    onpointerup: move |_| cancel_long_press(),
+                    onpointerleave: move |_| cancel_long_press(),
+                    oncontextmenu: move |e| e.prevent_default(),
                     onclick: move |_| {
-                        if *props.focused.peek(){
-                            if let Some((x, y)) = *cursor.peek(){
-                                check_entry(x, y, val);
-                            }
+                        if long_pressed.take() {
+                            return;
+                        }
+                        if settings.peek().input_mode == InputMode::DigitFirst {
+                            // select the digit, or deselect it if it was selected already
+                            let selected = *sticky_digit.peek() == Some(val);
+                            sticky_digit.set(if selected { None } else { Some(val) });
+                        } else if *props.focused.peek(){
+                            enter_selected(val);
                         }
                     },
-                    "{val}",
+                    {symbol(val)},
+                    // number of squares left to place this digit in
+                    span { class: "digit-count", "{remaining(val)}" }
                 }
             },
         }
+        // further actions for the square at the cursor
+        div {
+            class: "action-container",
+            button {
+                class: "exit-btn",
+                onclick: move |_| {
+                    if *props.focused.peek(){
+                        erase();
+                    }
+                },
+                {t(Msg::Erase)}
+            }
+            button {
+                class: "exit-btn",
+                disabled: game.read().history.is_empty(),
+                onclick: move |_| undo(),
+                {t(Msg::Undo)}
+            }
+            button {
+                class: if notes_mode() {"exit-btn action-active"} else {"exit-btn"},
+                aria_pressed: notes_mode(),
+                onclick: move |_| notes_mode.toggle(),
+                {t(Msg::Notes)}
+            }
+            button {
+                class: "exit-btn",
+                onclick: move |_| {
+                    let mode = settings.peek().input_mode.toggled();
+                    settings.write().input_mode = mode;
+                    sticky_digit.set(None);
+                },
+                {t(Msg::InputMode(settings.read().input_mode))}
+            }
+        }
+        // colours to mark the selected squares with, toggling the mark if all of them have it
+        div {
+            class: "palette",
+            for colour in 1..=COLOURS {
+                button {
+                    class: "swatch colour-{colour}",
+                    title: t(Msg::Colour(colour)),
+                    aria_label: t(Msg::Colour(colour)),
+                    onclick: move |_| paint(colour),
+                }
+            }
+            button {
+                class: "swatch",
+                title: t(Msg::ClearColour),
+                aria_label: t(Msg::ClearColour),
+                onclick: move |_| paint(0),
+                "×"
+            }
+        }
         }
     )
 }
 
 // State Definitions
 
-#[derive(Default, EnumIter, Display, Copy, Clone, PartialEq)]
+#[derive(
+    Default,
+    EnumIter,
+    Display,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
 /// Game difficulty, which translates to the number of cues given initially
 enum Difficulty {
     #[default]
@@ -348,26 +1032,30 @@ enum Difficulty {
     Challenge,
 }
 impl Difficulty {
-    /// Get the [`CatState`] that illustrates the reaction to
+    /// Get the [`CatSprite`] that illustrates the reaction to
     /// the given difficulty level in the menu screen
-    fn cat_state(&self) -> CatState {
+    fn cat_sprite(&self) -> CatSprite {
         match self {
-            Difficulty::Easy => CatState {
-                state: CatSprite::EasyReaction,
-            },
-            Difficulty::Medium => CatState {
-                state: CatSprite::MediumReaction,
-            },
-            Difficulty::Hard => CatState {
-                state: CatSprite::HardReaction,
-            },
-            Difficulty::Challenge => CatState {
-                state: CatSprite::ChallengeReaction,
-            },
+            Difficulty::Easy => CatSprite::EasyReaction,
+            Difficulty::Medium => CatSprite::MediumReaction,
+            Difficulty::Hard => CatSprite::HardReaction,
+            Difficulty::Challenge => CatSprite::ChallengeReaction,
         }
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
+/// Screens that can be shown instead of the game while no game is played, except for the
+/// settings, which can be opened anytime
+enum Screen {
+    Menu,
+    Stats,
+    Shop,
+    Wardrobe,
+    Achievements,
+    Settings,
+}
+
 // Components
 
 #[component]
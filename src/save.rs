@@ -0,0 +1,312 @@
+use dioxus_sdk::storage::{LocalStorage, StorageBacking};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    constants::PEERS,
+    storage::{add_field, read_raw, remove_raw, Legacy, Versioned},
+    sudoku::Sudoku,
+    Difficulty,
+};
+
+// SETTINGS
+
+/// Maximum number of moves that can be undone
+pub const HISTORY_LIMIT: usize = 500;
+/// Number of colours squares can be marked with, each styled by a `colour-<n>` CSS class
+pub const COLOURS: u8 = 8;
+/// Keys under which the game was stored before saves were versioned
+const LEGACY_KEYS: [&str; 3] = ["sudoku", "solution", "playing"];
+
+/// The state of the current game, persisted such that reloads don't revert progress
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveGame {
+    /// the current state of the board
+    pub sudoku: Sudoku,
+    /// the solution of the board, as a flat, row-major array
+    pub solution: Vec<u8>,
+    /// whether a game is currently played or not
+    pub playing: bool,
+    /// time spent playing the current game in milliseconds, excluding pauses
+    pub elapsed_ms: u64,
+    /// difficulty of the current game, unknown for games started before it was saved
+    pub difficulty: Option<Difficulty>,
+    /// number of wrong entries made in the current game
+    pub mistakes: u32,
+    /// number of hints used in the current game, as counted by the statistics, yarn and
+    /// achievements, which stays at zero as long as no hints can be taken
+    pub hints: u32,
+    /// candidates noted by the player for each square, as a bitmask where bit `v - 1` is set
+    /// if the digit `v` was noted
+    pub notes: Vec<u16>,
+    /// whether each square was given as a cue, which protects it from being changed
+    pub givens: Vec<bool>,
+    /// colour marked on each square by the player, from `1` to [`COLOURS`], or `0` if unmarked
+    pub colours: Vec<u8>,
+    /// moves that can be undone, most recent last, each consisting of the states of all
+    /// squares it changed from before the move
+    pub history: Vec<Vec<SquareState>>,
+}
+
+/// The state of a square from before it was changed, such that the change can be undone
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SquareState {
+    pub square: usize,
+    pub value: u8,
+    pub notes: u16,
+    pub colour: u8,
+}
+impl SaveGame {
+    /// Start a new game with the given board, solution and difficulty
+    pub fn new(sudoku: Sudoku, solution: [u8; 81], difficulty: Difficulty) -> Self {
+        SaveGame {
+            givens: (0..81).map(|i| !sudoku.is_zero(i % 9, i / 9)).collect(),
+            sudoku,
+            solution: solution.to_vec(),
+            playing: true,
+            difficulty: Some(difficulty),
+            ..SaveGame::initial()
+        }
+    }
+    /// The board as it was given at the start of the game, holding only the cues
+    pub fn puzzle(&self) -> Sudoku {
+        let mut puzzle = Sudoku::empty();
+        for i in (0..81).filter(|i| self.givens[*i]) {
+            puzzle.set(i, self.solution[i]);
+        }
+        puzzle
+    }
+    /// Check whether every square holds the digit of the solution
+    pub fn solved(&self) -> bool {
+        self.sudoku.filled() && (0..81).all(|i| self.sudoku.get(i % 9, i / 9) == self.solution[i])
+    }
+    /// Count the squares where the digit `val` is placed correctly
+    pub fn count_correct(&self, val: u8) -> usize {
+        (0..81)
+            .filter(|i| self.solution[*i] == val && self.sudoku.get(i % 9, i / 9) == val)
+            .count()
+    }
+    /// Check whether every square is filled while some contradict the solution, such that the
+    /// game can't be won without correcting them
+    pub fn filled_wrong(&self) -> bool {
+        self.sudoku.filled() && !self.solved()
+    }
+    /// Check whether the square with index `i` holds a digit that contradicts the solution
+    pub fn is_wrong(&self, i: usize) -> bool {
+        let val = self.sudoku.get(i % 9, i / 9);
+        val != 0 && val != self.solution[i]
+    }
+    /// Place the digit `val` in the square with index `i` as one move, clearing its notes and,
+    /// if `auto_notes` is set, removing `val` from the notes of all squares in the same units
+    pub fn place(&mut self, i: usize, val: u8, auto_notes: bool) {
+        let peers: Vec<usize> = if auto_notes {
+            PEERS[i]
+                .iter()
+                .copied()
+                .filter(|&p| self.has_note(p, val))
+                .collect()
+        } else {
+            vec![]
+        };
+        self.checkpoint(std::iter::once(i).chain(peers.iter().copied()));
+        self.sudoku.set(i, val);
+        self.notes[i] = 0;
+        for p in peers {
+            self.toggle_note(p, val);
+        }
+    }
+    /// Check whether the digit `val` is noted as a candidate for the square with index `i`
+    pub fn has_note(&self, i: usize, val: u8) -> bool {
+        self.notes[i] & (1 << (val - 1)) != 0
+    }
+    /// Add or remove the digit `val` as a candidate for the square with index `i`
+    pub fn toggle_note(&mut self, i: usize, val: u8) {
+        debug_assert!(0 < val && val < 10);
+        self.notes[i] ^= 1 << (val - 1);
+    }
+    /// Note the digit `val` as a candidate for all empty squares among `squares` in one move,
+    /// or remove it from all of them if it is noted in each already, returning whether any
+    /// square was changed
+    pub fn toggle_notes(&mut self, squares: &[usize], val: u8) -> bool {
+        let empty: Vec<usize> = squares
+            .iter()
+            .copied()
+            .filter(|&i| self.sudoku.is_zero(i % 9, i / 9))
+            .collect();
+        if empty.is_empty() {
+            return false;
+        }
+        let noted = empty.iter().all(|&i| self.has_note(i, val));
+        self.checkpoint(empty.iter().copied());
+        for i in empty {
+            if self.has_note(i, val) == noted {
+                self.toggle_note(i, val);
+            }
+        }
+        true
+    }
+    /// Mark all `squares` with the given colour in one move, or remove the colour if all of
+    /// them are marked with it already, returning whether any square was changed
+    pub fn paint(&mut self, squares: &[usize], colour: u8) -> bool {
+        debug_assert!(colour <= COLOURS);
+        let colour = if squares.iter().all(|&i| self.colours[i] == colour) {
+            0
+        } else {
+            colour
+        };
+        if squares.iter().all(|&i| self.colours[i] == colour) {
+            return false;
+        }
+        self.checkpoint(squares.iter().copied());
+        for &i in squares {
+            self.colours[i] = colour;
+        }
+        true
+    }
+    /// Record the current state of the given squares as a move in the history, such that the
+    /// changes made to them afterwards can be undone
+    pub fn checkpoint(&mut self, squares: impl IntoIterator<Item = usize>) {
+        let states = squares
+            .into_iter()
+            .map(|square| SquareState {
+                square,
+                value: self.sudoku.get(square % 9, square / 9),
+                notes: self.notes[square],
+                colour: self.colours[square],
+            })
+            .collect();
+        self.history.push(states);
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+    }
+    /// Revert the most recent move in the history, returning whether there was one
+    pub fn undo(&mut self) -> bool {
+        let Some(states) = self.history.pop() else {
+            return false;
+        };
+        for state in states {
+            self.sudoku.set(state.square, state.value);
+            self.notes[state.square] = state.notes;
+            self.colours[state.square] = state.colour;
+        }
+        true
+    }
+    /// Remove the values and notes of all `squares` in one move, except for cues, returning
+    /// whether anything was erased
+    pub fn erase(&mut self, squares: &[usize]) -> bool {
+        let erasable: Vec<usize> = squares
+            .iter()
+            .copied()
+            .filter(|&i| {
+                !self.givens[i] && (!self.sudoku.is_zero(i % 9, i / 9) || self.notes[i] != 0)
+            })
+            .collect();
+        if erasable.is_empty() {
+            return false;
+        }
+        self.checkpoint(erasable.iter().copied());
+        for i in erasable {
+            self.sudoku.set(i, 0);
+            self.notes[i] = 0;
+        }
+        true
+    }
+}
+
+impl Versioned for SaveGame {
+    const KEY: &'static str = "save";
+    const VERSION: u32 = 6;
+
+    fn initial() -> Self {
+        SaveGame {
+            sudoku: Sudoku::empty(),
+            solution: vec![0u8; 81],
+            playing: false,
+            elapsed_ms: 0,
+            difficulty: None,
+            mistakes: 0,
+            hints: 0,
+            notes: vec![0u16; 81],
+            givens: vec![false; 81],
+            colours: vec![0u8; 81],
+            history: vec![],
+        }
+    }
+
+    fn migrate(version: u32, mut data: Value) -> Result<Value, String> {
+        match version {
+            // version 2 introduced the game timer
+            1 => add_field(&mut data, "elapsed_ms", json!(0))?,
+            // version 3 introduced statistics, which require the difficulty, mistakes and hints
+            2 => {
+                add_field(&mut data, "difficulty", Value::Null)?;
+                add_field(&mut data, "mistakes", json!(0))?;
+                add_field(&mut data, "hints", json!(0))?;
+            }
+            // version 4 introduced notes
+            3 => add_field(&mut data, "notes", Value::from(vec![0u16; 81]))?,
+            // version 5 introduced erasing and undoing moves, which requires to tell cues apart
+            // from entries. For older games this is impossible, so all filled squares are
+            // protected as cues.
+            4 => {
+                let givens: Vec<bool> = data["sudoku"]["grid"]
+                    .as_array()
+                    .ok_or("missing grid")?
+                    .iter()
+                    .map(|val| val.as_u64() != Some(0))
+                    .collect();
+                add_field(&mut data, "givens", Value::from(givens))?;
+                add_field(&mut data, "history", json!([]))?;
+            }
+            // version 6 introduced colour marks, which are also recorded in the history
+            5 => {
+                add_field(&mut data, "colours", Value::from(vec![0u8; 81]))?;
+                let history = data["history"].as_array_mut().ok_or("missing history")?;
+                for states in history {
+                    for state in states.as_array_mut().ok_or("expected a move")? {
+                        add_field(state, "colour", json!(0))?;
+                    }
+                }
+            }
+            _ => return Err(format!("no migration from version {version}")),
+        }
+        Ok(data)
+    }
+
+    /// Before saves were versioned, the sudoku, solution and whether a game was played were
+    /// stored under separate keys, which together make up version 1 of the [`SaveGame`]
+    fn legacy() -> Option<Legacy> {
+        let raw = LEGACY_KEYS.map(read_raw);
+        if raw.iter().all(Option::is_none) {
+            return None;
+        }
+        let [sudoku, solution, playing] = &raw;
+        Some(Legacy {
+            raw: json!({ "sudoku": sudoku, "solution": solution, "playing": playing }).to_string(),
+            data: read_legacy(playing.is_some()),
+        })
+    }
+    fn remove_legacy() {
+        for key in LEGACY_KEYS {
+            remove_raw(key);
+        }
+    }
+}
+
+/// Read the game stored under the [`LEGACY_KEYS`], where the game wasn't played unless
+/// `playing` was stored
+fn read_legacy(playing: bool) -> Result<(u32, Value), String> {
+    let unreadable = |key: &str| format!("unreadable {key}");
+    let sudoku =
+        LocalStorage::get::<Sudoku>(&"sudoku".to_string()).ok_or_else(|| unreadable("sudoku"))?;
+    let solution = LocalStorage::get::<Vec<u8>>(&"solution".to_string())
+        .ok_or_else(|| unreadable("solution"))?;
+    let playing = playing
+        && LocalStorage::get::<bool>(&"playing".to_string())
+            .ok_or_else(|| unreadable("playing"))?;
+    Ok((
+        1,
+        json!({ "sudoku": sudoku, "solution": solution, "playing": playing }),
+    ))
+}
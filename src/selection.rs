@@ -0,0 +1,71 @@
+use std::collections::BTreeSet;
+
+/// The squares selected in the grid, identified by their row-major index. The cursor is the
+/// square selected last, which values are entered at, while notes, colours and erasing apply
+/// to all selected squares.
+#[derive(Clone, Default, PartialEq)]
+pub struct Selection {
+    /// the square selected last
+    cursor: Option<usize>,
+    /// the square that ranges selected with Shift start from
+    anchor: Option<usize>,
+    /// all selected squares, including the cursor
+    squares: BTreeSet<usize>,
+}
+impl Selection {
+    /// Coordinates of the cursor, if any square is selected
+    pub fn cursor(&self) -> Option<(usize, usize)> {
+        self.cursor.map(|i| (i % 9, i / 9))
+    }
+    /// Indices of all selected squares
+    pub fn squares(&self) -> Vec<usize> {
+        self.squares.iter().copied().collect()
+    }
+    /// Check whether the square at `x`,`y` is selected
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        self.squares.contains(&(x + 9 * y))
+    }
+    /// Select only the square at `x`,`y`
+    pub fn select(&mut self, x: usize, y: usize) {
+        let i = x + 9 * y;
+        self.squares = BTreeSet::from([i]);
+        self.cursor = Some(i);
+        self.anchor = Some(i);
+    }
+    /// Add the square at `x`,`y` to the selection and move the cursor to it, as when dragging
+    pub fn add(&mut self, x: usize, y: usize) {
+        let i = x + 9 * y;
+        self.squares.insert(i);
+        self.cursor = Some(i);
+    }
+    /// Add the square at `x`,`y` to the selection, or remove it if it was selected already
+    pub fn toggle(&mut self, x: usize, y: usize) {
+        let i = x + 9 * y;
+        if self.squares.remove(&i) {
+            if self.cursor == Some(i) {
+                self.cursor = self.squares.last().copied();
+            }
+        } else {
+            self.squares.insert(i);
+            self.cursor = Some(i);
+        }
+        self.anchor = self.cursor;
+    }
+    /// Add the rectangle of squares spanned by the anchor and the square at `x`,`y`
+    pub fn extend(&mut self, x: usize, y: usize) {
+        let Some(anchor) = self.anchor else {
+            return self.select(x, y);
+        };
+        let (ax, ay) = (anchor % 9, anchor / 9);
+        for sy in ay.min(y)..=ay.max(y) {
+            for sx in ax.min(x)..=ax.max(x) {
+                self.squares.insert(sx + 9 * sy);
+            }
+        }
+        self.cursor = Some(x + 9 * y);
+    }
+    /// Deselect all squares
+    pub fn clear(&mut self) {
+        *self = Selection::default();
+    }
+}
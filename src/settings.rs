@@ -0,0 +1,379 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::{
+    audio::{AudioSettings, VOLUMES},
+    feedback::HapticSettings,
+    gestures::{
+        next_preset, GestureSettings, LONG_PRESS_DURATIONS, SWIPE_DISTANCES, TAP_DURATIONS,
+    },
+    i18n::{use_t, Locale, Msg},
+    keymap::{Action, KeyMap},
+    storage::{add_field, Versioned},
+    symbols::SymbolSet,
+    theme::Theme,
+};
+
+/// Preferences of the player, persisted in local storage and provided to all components via
+/// the context as a `Signal<Settings>`
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    pub input_mode: InputMode,
+    pub keymap: KeyMap,
+    pub mistake_mode: MistakeMode,
+    /// whether placing a digit removes it from the notes of all squares in the same units
+    pub auto_notes: bool,
+    /// whether the row and column of the cursor are highlighted
+    pub highlight_lines: bool,
+    /// whether squares holding the same digit as the cursor are highlighted
+    pub highlight_digits: bool,
+    pub show_timer: bool,
+    /// whether the cat plays animations, such as fireworks after a win
+    pub animations: bool,
+    /// the chosen colour theme, or `None` to follow the system
+    pub theme: Option<Theme>,
+    /// the chosen language, or `None` to detect it from the browser
+    pub locale: Option<Locale>,
+    /// symbols displayed for the digits
+    pub symbols: SymbolSet,
+    /// thresholds of the touch gestures on the grid and number pad
+    pub gestures: GestureSettings,
+    pub audio: AudioSettings,
+    /// which moments of the game vibrate the device
+    pub haptics: HapticSettings,
+}
+impl Versioned for Settings {
+    const KEY: &'static str = "settings";
+    const VERSION: u32 = 12;
+    fn initial() -> Self {
+        Settings {
+            input_mode: InputMode::default(),
+            keymap: KeyMap::default(),
+            mistake_mode: MistakeMode::default(),
+            auto_notes: false,
+            highlight_lines: true,
+            highlight_digits: true,
+            show_timer: true,
+            animations: true,
+            theme: None,
+            locale: None,
+            symbols: SymbolSet::default(),
+            gestures: GestureSettings::default(),
+            audio: AudioSettings::default(),
+            haptics: HapticSettings::default(),
+        }
+    }
+    fn migrate(version: u32, mut data: Value) -> Result<Value, String> {
+        match version {
+            // version 2 introduced configurable key bindings
+            1 => add_field(
+                &mut data,
+                "keymap",
+                serde_json::to_value(KeyMap::default()).map_err(|e| e.to_string())?,
+            )?,
+            // version 3 introduced colour marks and multiple selected squares
+            2 => {
+                let keymap = data.get_mut("keymap").ok_or("missing keymap")?;
+                let mut map: KeyMap =
+                    serde_json::from_value(keymap.take()).map_err(|e| e.to_string())?;
+                map.add_defaults(&[Action::ClearColour, Action::ClearSelection]);
+                *keymap = serde_json::to_value(map).map_err(|e| e.to_string())?;
+            }
+            // version 4 introduced the settings screen, defaulting to the previous behaviour
+            3 => {
+                add_field(&mut data, "mistake_mode", json!(MistakeMode::default()))?;
+                add_field(&mut data, "auto_notes", json!(false))?;
+                add_field(&mut data, "highlight_lines", json!(true))?;
+                add_field(&mut data, "highlight_digits", json!(true))?;
+                add_field(&mut data, "show_timer", json!(true))?;
+                add_field(&mut data, "animations", json!(true))?;
+            }
+            // version 5 introduced colour themes
+            4 => add_field(&mut data, "theme", Value::Null)?,
+            // version 6 introduced translations of the user interface
+            5 => add_field(&mut data, "locale", Value::Null)?,
+            // version 7 introduced alternative symbol sets
+            6 => add_field(&mut data, "symbols", json!(SymbolSet::default()))?,
+            // version 8 introduced touch gestures
+            7 => add_field(&mut data, "gestures", json!(GestureSettings::default()))?,
+            // version 9 introduced sounds
+            8 => add_field(&mut data, "audio", json!(AudioSettings::default()))?,
+            // version 10 introduced vibration
+            9 => add_field(&mut data, "haptics", json!(HapticSettings::default()))?,
+            // version 11 let Tab move the focus past the empty squares, binding it to them
+            // wherever it is still free
+            10 => {
+                let keymap = data.get_mut("keymap").ok_or("missing keymap")?;
+                let mut map: KeyMap =
+                    serde_json::from_value(keymap.take()).map_err(|e| e.to_string())?;
+                map.add_defaults(&[Action::NextEmpty, Action::PreviousEmpty]);
+                *keymap = serde_json::to_value(map).map_err(|e| e.to_string())?;
+            }
+            // version 12 removed the action revealing hints
+            11 => {
+                let bindings = data
+                    .pointer_mut("/keymap/bindings")
+                    .and_then(Value::as_array_mut)
+                    .ok_or("missing key bindings")?;
+                bindings.retain(|binding| binding[1] != "Hint");
+            }
+            _ => return Err(format!("no migration from version {version}")),
+        }
+        Ok(data)
+    }
+}
+
+/// How digits are entered into the grid
+#[derive(Default, EnumIter, Display, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputMode {
+    /// select a square first, then the digit to enter into it
+    #[default]
+    #[strum(to_string = "Square first")]
+    SquareFirst,
+    /// select a digit first, then each square to enter it into
+    #[strum(to_string = "Digit first")]
+    DigitFirst,
+}
+impl InputMode {
+    /// Get the other input mode
+    pub fn toggled(&self) -> Self {
+        match self {
+            InputMode::SquareFirst => InputMode::DigitFirst,
+            InputMode::DigitFirst => InputMode::SquareFirst,
+        }
+    }
+}
+
+/// How digits that contradict the solution are treated
+#[derive(Default, EnumIter, Display, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MistakeMode {
+    /// wrong digits are not entered, but counted as mistakes
+    #[default]
+    Reject,
+    /// wrong digits are entered and marked as wrong
+    Show,
+    /// wrong digits are entered without any feedback until the grid is solved
+    Off,
+}
+impl MistakeMode {
+    /// Get the next mistake mode, cycling back to the first one
+    pub fn next(&self) -> Self {
+        MistakeMode::iter()
+            .cycle()
+            .skip_while(|mode| mode != self)
+            .nth(1)
+            .unwrap_or_default()
+    }
+}
+
+/// Screen to change the [`Settings`] in the context, each of which is changed by clicking
+/// its current value
+#[component]
+pub fn SettingsScreen(on_back: Callback) -> Element {
+    let mut settings = use_context::<Signal<Settings>>();
+    let theme = use_context::<Memo<Theme>>();
+    let locale = use_context::<Memo<Locale>>();
+    let t = use_t();
+    let switch = move |on: bool| if on { t(Msg::On) } else { t(Msg::Off) };
+    let current = settings.read().clone();
+
+    rsx!(
+        div { class: "stats",
+            table { class: "settings",
+                SettingRow {
+                    label: t(Msg::Input),
+                    value: t(Msg::InputMode(current.input_mode)),
+                    on_change: move |_| {
+                        let mode = settings.peek().input_mode.toggled();
+                        settings.write().input_mode = mode;
+                    },
+                }
+                SettingRow {
+                    label: t(Msg::Theme),
+                    value: t(current.theme.map_or(
+                        Msg::System(&t(Msg::ThemeName(theme()))),
+                        Msg::ThemeName,
+                    )),
+                    on_change: move |_| {
+                        let choice = Theme::next_choice(settings.peek().theme);
+                        settings.write().theme = choice;
+                    },
+                }
+                SettingRow {
+                    label: t(Msg::Language),
+                    value: current.locale.map_or(t(Msg::System(locale().name())), |l| {
+                        l.name().to_string()
+                    }),
+                    on_change: move |_| {
+                        let choice = Locale::next_choice(settings.peek().locale);
+                        settings.write().locale = choice;
+                    },
+                }
+                SettingRow {
+                    label: t(Msg::Symbols),
+                    value: current.symbols.sample(),
+                    on_change: move |_| {
+                        let next = settings.peek().symbols.next();
+                        settings.write().symbols = next;
+                    },
+                }
+                SettingRow {
+                    label: t(Msg::WrongDigits),
+                    value: t(Msg::MistakeMode(current.mistake_mode)),
+                    on_change: move |_| {
+                        let mode = settings.peek().mistake_mode.next();
+                        settings.write().mistake_mode = mode;
+                    },
+                }
+                SettingRow {
+                    label: t(Msg::AutoNotes),
+                    value: switch(current.auto_notes),
+                    on_change: move |_| settings.write().auto_notes ^= true,
+                }
+                SettingRow {
+                    label: t(Msg::HighlightLines),
+                    value: switch(current.highlight_lines),
+                    on_change: move |_| settings.write().highlight_lines ^= true,
+                }
+                SettingRow {
+                    label: t(Msg::HighlightDigits),
+                    value: switch(current.highlight_digits),
+                    on_change: move |_| settings.write().highlight_digits ^= true,
+                }
+                SettingRow {
+                    label: t(Msg::Timer),
+                    value: switch(current.show_timer),
+                    on_change: move |_| settings.write().show_timer ^= true,
+                }
+                SettingRow {
+                    label: t(Msg::Animations),
+                    value: switch(current.animations),
+                    on_change: move |_| settings.write().animations ^= true,
+                }
+                SettingRow {
+                    label: t(Msg::Volume),
+                    value: locale().percent(current.audio.volume as f64 / 100.),
+                    on_change: move |_| {
+                        let audio = &mut settings.write().audio;
+                        audio.volume = next_preset(&VOLUMES, audio.volume);
+                    },
+                }
+                SettingRow {
+                    label: t(Msg::SoundEffects),
+                    value: switch(current.audio.effects),
+                    on_change: move |_| settings.write().audio.effects ^= true,
+                }
+                SettingRow {
+                    label: t(Msg::CatSounds),
+                    value: switch(current.audio.cat),
+                    on_change: move |_| settings.write().audio.cat ^= true,
+                }
+                SettingRow {
+                    label: t(Msg::Fanfare),
+                    value: switch(current.audio.fanfare),
+                    on_change: move |_| settings.write().audio.fanfare ^= true,
+                }
+                SettingRow {
+                    label: t(Msg::VibratePlaced),
+                    value: switch(current.haptics.placed),
+                    on_change: move |_| settings.write().haptics.placed ^= true,
+                }
+                SettingRow {
+                    label: t(Msg::VibrateUnit),
+                    value: switch(current.haptics.unit_completed),
+                    on_change: move |_| settings.write().haptics.unit_completed ^= true,
+                }
+                SettingRow {
+                    label: t(Msg::VibrateMistake),
+                    value: switch(current.haptics.mistake),
+                    on_change: move |_| settings.write().haptics.mistake ^= true,
+                }
+                SettingRow {
+                    label: t(Msg::VibrateWon),
+                    value: switch(current.haptics.won),
+                    on_change: move |_| settings.write().haptics.won ^= true,
+                }
+                SettingRow {
+                    label: t(Msg::SwipeDistance),
+                    value: t(Msg::Pixels(&locale().number(current.gestures.swipe_distance))),
+                    on_change: move |_| {
+                        let gestures = &mut settings.write().gestures;
+                        gestures.swipe_distance = next_preset(&SWIPE_DISTANCES, gestures.swipe_distance);
+                    },
+                }
+                SettingRow {
+                    label: t(Msg::LongPress),
+                    value: t(Msg::Milliseconds(&locale().number(current.gestures.long_press_ms as f64))),
+                    on_change: move |_| {
+                        let gestures = &mut settings.write().gestures;
+                        gestures.long_press_ms = next_preset(&LONG_PRESS_DURATIONS, gestures.long_press_ms);
+                    },
+                }
+                SettingRow {
+                    label: t(Msg::MultiTap),
+                    value: t(Msg::Milliseconds(&locale().number(current.gestures.tap_ms as f64))),
+                    on_change: move |_| {
+                        let gestures = &mut settings.write().gestures;
+                        gestures.tap_ms = next_preset(&TAP_DURATIONS, gestures.tap_ms);
+                    },
+                }
+            }
+            div { class: "stats-actions",
+                button { class: "exit-btn", onclick: move |_| on_back(()), {t(Msg::Back)} }
+            }
+        }
+    )
+}
+
+/// Row of the [`SettingsScreen`] showing the name and current value of a setting
+#[component]
+fn SettingRow(label: String, value: String, on_change: Callback) -> Element {
+    rsx!(
+        tr {
+            th { "{label}" }
+            td {
+                button { class: "exit-btn", onclick: move |_| on_change(()), "{value}" }
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::KeyBinding;
+
+    #[test]
+    fn migration_to_11_keeps_user_bindings() {
+        let user = KeyMap {
+            bindings: vec![
+                (KeyBinding::new(Code::KeyX), Action::NextEmpty),
+                (KeyBinding::new(Code::Tab), Action::Undo),
+            ],
+        };
+        let mut data = serde_json::to_value(Settings::initial()).unwrap();
+        data["keymap"] = serde_json::to_value(&user).unwrap();
+        let data = Settings::migrate(10, data).unwrap();
+        let map: KeyMap = serde_json::from_value(data["keymap"].clone()).unwrap();
+        assert!(map.action(&KeyBinding::new(Code::KeyX)) == Some(Action::NextEmpty));
+        assert!(map.action(&KeyBinding::new(Code::Tab)) == Some(Action::Undo));
+        // only the free binding is added
+        assert!(map.action(&KeyBinding::new(Code::Tab).shift()) == Some(Action::PreviousEmpty));
+        assert_eq!(map.bindings.len(), 3);
+    }
+
+    #[test]
+    fn migration_to_12_removes_hint_bindings() {
+        let mut data = serde_json::to_value(Settings::initial()).unwrap();
+        data["keymap"]["bindings"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!([KeyBinding::new(Code::KeyI), "Hint"]));
+        let data = Settings::migrate(11, data).unwrap();
+        let map: KeyMap = serde_json::from_value(data["keymap"].clone()).unwrap();
+        assert!(map == KeyMap::default());
+    }
+}
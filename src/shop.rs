@@ -0,0 +1,203 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::{
+    cat::{Clip, GameEvent, NOM, POUNCE, SPIN},
+    i18n::{use_t, Locale, Msg},
+    storage::Versioned,
+    Difficulty,
+};
+
+// SETTINGS
+
+/// Number of transactions kept in the ledger, beyond which the oldest ones are dropped
+pub const LEDGER_LIMIT: usize = 50;
+/// Number of recent transactions shown in the shop
+pub const LEDGER_SHOWN: usize = 5;
+
+/// Yarn earned for winning a game of the given `difficulty`, which is doubled without `hints`
+/// and doubled again without `mistakes`
+pub fn reward(difficulty: Difficulty, hints: u32, mistakes: u32) -> u32 {
+    let mut yarn = match difficulty {
+        Difficulty::Easy => 1,
+        Difficulty::Medium => 2,
+        Difficulty::Hard => 3,
+        Difficulty::Challenge => 5,
+    };
+    if hints == 0 {
+        yarn *= 2;
+    }
+    if mistakes == 0 {
+        yarn *= 2;
+    }
+    yarn
+}
+
+/// Toys and treats that can be bought with yarn and given to the cat
+#[derive(EnumIter, Display, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Item {
+    #[strum(to_string = "Toy mouse")]
+    Mouse,
+    Feather,
+    #[strum(to_string = "Ball of yarn")]
+    Yarn,
+    Fish,
+    Milk,
+    Cake,
+}
+impl Item {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Item::Mouse => "🐭",
+            Item::Feather => "🪶",
+            Item::Yarn => "🧶",
+            Item::Fish => "🐟",
+            Item::Milk => "🥛",
+            Item::Cake => "🍰",
+        }
+    }
+    /// Price in yarn
+    pub fn price(&self) -> u32 {
+        match self {
+            Item::Mouse => 5,
+            Item::Feather => 8,
+            Item::Yarn => 12,
+            Item::Fish => 3,
+            Item::Milk => 4,
+            Item::Cake => 20,
+        }
+    }
+    /// Animation the cat plays when given the item
+    pub fn clip(&self) -> &'static Clip {
+        match self {
+            Item::Mouse => &POUNCE,
+            Item::Feather | Item::Yarn => &SPIN,
+            Item::Fish | Item::Milk | Item::Cake => &NOM,
+        }
+    }
+}
+
+/// Reason for a change of the yarn balance
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Transaction {
+    /// yarn earned by winning a game
+    Won { difficulty: Difficulty, yarn: u32 },
+    /// yarn spent on an item
+    Bought { item: Item, yarn: u32 },
+}
+
+/// Balance of yarn and the most recent transactions, persisted in local storage
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ledger {
+    pub balance: u32,
+    /// transactions, most recent last
+    pub history: Vec<Transaction>,
+}
+impl Ledger {
+    fn record(&mut self, transaction: Transaction) {
+        self.history.push(transaction);
+        if self.history.len() > LEDGER_LIMIT {
+            self.history.remove(0);
+        }
+    }
+    /// Add the yarn earned for a won game, returning the amount
+    pub fn earn(&mut self, difficulty: Difficulty, hints: u32, mistakes: u32) -> u32 {
+        let yarn = reward(difficulty, hints, mistakes);
+        self.balance += yarn;
+        self.record(Transaction::Won { difficulty, yarn });
+        yarn
+    }
+    /// Buy the `item` if the balance suffices, returning whether it was bought
+    pub fn buy(&mut self, item: Item) -> bool {
+        if self.balance < item.price() {
+            return false;
+        }
+        self.balance -= item.price();
+        self.record(Transaction::Bought {
+            item,
+            yarn: item.price(),
+        });
+        true
+    }
+}
+impl Versioned for Ledger {
+    const KEY: &'static str = "ledger";
+    const VERSION: u32 = 1;
+    fn initial() -> Self {
+        Ledger::default()
+    }
+}
+
+/// Screen showing the yarn balance, where toys and treats can be bought for the cat, which
+/// reacts to them right away
+#[component]
+pub fn ShopScreen(on_back: Callback) -> Element {
+    let mut ledger = use_context::<Signal<Ledger>>();
+    let on_cat = use_context::<Callback<GameEvent>>();
+    let locale = use_context::<Memo<Locale>>();
+    let t = use_t();
+    let balance = ledger.read().balance;
+
+    rsx!(
+        div { class: "stats",
+            div { class: "streak",
+                img { class: "streak-icon", src: YARN, draggable: false, alt: t(Msg::Yarn) }
+                span { class: "streak-current", {locale().number(balance)} }
+            }
+            table { class: "settings",
+                for item in Item::iter() {
+                    tr {
+                        th {
+                            span { class: "wardrobe-item", aria_hidden: true, {item.emoji()} }
+                            {t(Msg::Item(item))}
+                        }
+                        td { {t(Msg::Price(&locale().number(item.price())))} }
+                        td {
+                            button {
+                                class: "exit-btn",
+                                disabled: balance < item.price(),
+                                onclick: move |_| {
+                                    if ledger.write().buy(item) {
+                                        on_cat(GameEvent::Gift(item));
+                                    }
+                                },
+                                {t(Msg::Buy)}
+                            }
+                        }
+                    }
+                }
+            }
+            table { class: "settings",
+                for transaction in ledger.read().history.iter().rev().take(LEDGER_SHOWN) {
+                    tr {
+                        match *transaction {
+                            Transaction::Won { difficulty, yarn } => rsx!(
+                                th { {t(Msg::Difficulty(difficulty))} }
+                                td { "+{locale().number(yarn)}" }
+                            ),
+                            Transaction::Bought { item, yarn } => rsx!(
+                                th { {t(Msg::Item(item))} }
+                                td { "−{locale().number(yarn)}" }
+                            ),
+                        }
+                    }
+                }
+            }
+            button { class: "exit-btn", onclick: move |_| on_back(()), {t(Msg::Back)} }
+        }
+    )
+}
+
+// ASSETS
+
+const YARN: Asset = asset!(
+    "assets/images/yarn.png",
+    ImageAssetOptions::new()
+        .with_size(ImageSize::Manual {
+            width: 96,
+            height: 96
+        })
+        .with_format(ImageFormat::Avif)
+);
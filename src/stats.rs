@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use strum::IntoEnumIterator;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+use crate::{
+    cat::{CatSprite, GameEvent},
+    i18n::{use_t, Locale, Msg},
+    storage::{add_field, export, import, Versioned},
+    timer::{format_time, local_day},
+    Difficulty,
+};
+
+/// Statistics about the games of one [`Difficulty`]
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyStats {
+    /// number of games started
+    pub started: u32,
+    /// number of games won
+    pub finished: u32,
+    /// fastest time of a won game in milliseconds
+    pub best_ms: Option<u64>,
+    /// sum of the times of all won games in milliseconds
+    pub total_ms: u64,
+    /// number of hints used in won games
+    pub hints: u32,
+    /// number of mistakes made in won games
+    pub mistakes: u32,
+}
+impl DifficultyStats {
+    /// Average time of a won game in milliseconds
+    pub fn average_ms(&self) -> Option<u64> {
+        (self.finished > 0).then(|| self.total_ms / self.finished as u64)
+    }
+    /// Fraction of started games that were won
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.started > 0).then(|| self.finished as f64 / self.started as f64)
+    }
+}
+
+/// Consecutive days on which at least one puzzle was completed
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Streak {
+    /// the last day a puzzle was completed on, as returned by [`crate::timer::local_day`]
+    pub last_day: Option<i64>,
+    /// length of the current streak in days
+    pub current: u32,
+    /// length of the longest streak ever in days
+    pub longest: u32,
+}
+impl Streak {
+    /// Count a puzzle completed on `today`, returning whether this extended the streak
+    pub fn complete(&mut self, today: i64) -> Option<StreakChange> {
+        match self.last_day {
+            Some(last) if last == today => return None,
+            Some(last) if last == today - 1 => self.current += 1,
+            _ => self.current = 1,
+        }
+        self.last_day = Some(today);
+        self.longest = self.longest.max(self.current);
+        Some(StreakChange::Extended(self.current))
+    }
+    /// Reset the streak if no puzzle was completed yesterday or today, returning whether this
+    /// broke the streak
+    pub fn check(&mut self, today: i64) -> Option<StreakChange> {
+        match self.last_day {
+            Some(last) if last < today - 1 && self.current > 0 => {
+                let lost = self.current;
+                self.current = 0;
+                Some(StreakChange::Broken(lost))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A change in the [`Streak`] that the cat reacts to
+#[derive(Clone, Copy, PartialEq)]
+pub enum StreakChange {
+    /// the streak was extended to the given number of days
+    Extended(u32),
+    /// the streak of the given number of days was broken
+    Broken(u32),
+}
+impl StreakChange {
+    /// Get the [`CatSprite`] that illustrates the reaction to the change
+    pub fn cat_sprite(&self) -> CatSprite {
+        match self {
+            StreakChange::Extended(_) => CatSprite::StreakExtended,
+            StreakChange::Broken(_) => CatSprite::StreakBroken,
+        }
+    }
+}
+
+/// Statistics across all games, persisted in local storage
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub difficulties: BTreeMap<Difficulty, DifficultyStats>,
+    pub streak: Streak,
+}
+impl Stats {
+    /// Get the statistics for the given difficulty
+    pub fn get(&self, difficulty: Difficulty) -> DifficultyStats {
+        self.difficulties
+            .get(&difficulty)
+            .cloned()
+            .unwrap_or_default()
+    }
+    /// Count a newly started game
+    pub fn record_start(&mut self, difficulty: Difficulty) {
+        self.difficulties.entry(difficulty).or_default().started += 1;
+    }
+    /// Count a won game that took `elapsed_ms` to solve on `today`, returning whether this
+    /// extended the streak
+    pub fn record_win(
+        &mut self,
+        difficulty: Difficulty,
+        elapsed_ms: u64,
+        hints: u32,
+        mistakes: u32,
+        today: i64,
+    ) -> Option<StreakChange> {
+        let stats = self.difficulties.entry(difficulty).or_default();
+        stats.finished += 1;
+        stats.total_ms += elapsed_ms;
+        stats.best_ms = Some(
+            stats
+                .best_ms
+                .map_or(elapsed_ms, |best| best.min(elapsed_ms)),
+        );
+        stats.hints += hints;
+        stats.mistakes += mistakes;
+        self.streak.complete(today)
+    }
+}
+impl Versioned for Stats {
+    const KEY: &'static str = "stats";
+    const VERSION: u32 = 2;
+    fn initial() -> Self {
+        Stats::default()
+    }
+    fn migrate(version: u32, mut data: Value) -> Result<Value, String> {
+        match version {
+            // version 2 introduced the daily streak
+            1 => add_field(
+                &mut data,
+                "streak",
+                json!({ "last_day": null, "current": 0, "longest": 0 }),
+            )?,
+            _ => return Err(format!("no migration from version {version}")),
+        }
+        Ok(data)
+    }
+}
+
+/// Screen listing the [`Stats`] for each difficulty, which can be exported to and imported
+/// from a JSON file.
+#[component]
+pub fn StatsScreen(on_back: Callback) -> Element {
+    let mut stats = use_context::<Signal<Stats>>();
+    let locale = use_context::<Memo<Locale>>();
+    let t = use_t();
+    let mut import_error: Signal<Option<String>> = use_signal(move || None);
+    let optional_time = |ms: Option<u64>| ms.map_or("-".to_string(), format_time);
+    let export_href = use_memo(move || {
+        format!(
+            "data:application/json;charset=utf-8,{}",
+            js_sys::encode_uri_component(&export(&*stats.read()))
+        )
+    });
+
+    rsx!(
+        div { class: "stats",
+            table {
+                tr {
+                    th {}
+                    th { {t(Msg::Started)} }
+                    th { {t(Msg::Won)} }
+                    th { {t(Msg::WinRate)} }
+                    th { {t(Msg::Best)} }
+                    th { {t(Msg::Average)} }
+                    th { {t(Msg::Hints)} }
+                    th { {t(Msg::Mistakes)} }
+                }
+                for diff in Difficulty::iter() {
+                    {
+                        let s = stats.read().get(diff);
+                        rsx!(
+                            tr {
+                                th { {t(Msg::Difficulty(diff))} }
+                                td { {locale().number(s.started)} }
+                                td { {locale().number(s.finished)} }
+                                td { {s.win_rate().map_or("-".to_string(), |r| locale().percent(r))} }
+                                td { {optional_time(s.best_ms)} }
+                                td { {optional_time(s.average_ms())} }
+                                td { {locale().number(s.hints)} }
+                                td { {locale().number(s.mistakes)} }
+                            }
+                        )
+                    }
+                }
+            }
+            div { class: "stats-actions",
+                a {
+                    class: "exit-btn",
+                    href: "{export_href}",
+                    download: "cadoku-stats.json",
+                    {t(Msg::Export)}
+                }
+                label {
+                    class: "exit-btn",
+                    {t(Msg::Import)}
+                    input {
+                        r#type: "file",
+                        accept: ".json,application/json",
+                        style: "display: none;",
+                        onchange: move |e| async move {
+                            let Some(files) = e.files() else { return };
+                            for name in files.files() {
+                                let Some(raw) = files.read_file_to_string(&name).await else {
+                                    import_error.set(Some(t(Msg::FileUnreadable(&name))));
+                                    continue;
+                                };
+                                match import::<Stats>(&raw) {
+                                    Ok(imported) => {
+                                        stats.set(imported);
+                                        import_error.set(None);
+                                    }
+                                    Err(e) => import_error.set(Some(t(Msg::ImportError(&e)))),
+                                }
+                            }
+                        },
+                    }
+                }
+                button { class: "exit-btn", onclick: move |_| on_back(()), {t(Msg::Back)} }
+            }
+            if let Some(e) = import_error() {
+                p { class: "notice", "{e}" }
+            }
+        }
+    )
+}
+
+/// Hook that resets the [`Streak`] once it is broken and lets the cat react to it. Since the app
+/// may stay open across days, the streak is checked on load, whenever the page becomes visible
+/// again and through the returned callback, such as when a game starts.
+pub fn use_streak_check(mut stats: Signal<Stats>, on_cat: Callback<GameEvent>) -> Callback {
+    let check = use_callback(move |_| {
+        if let Some(change) = stats.write().streak.check(local_day()) {
+            on_cat(GameEvent::Streak(change));
+        }
+    });
+    use_effect(move || check(()));
+    use_hook(move || {
+        let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+            return;
+        };
+        let target = document.clone();
+        let on_visibility_change = Closure::<dyn FnMut()>::new(move || {
+            if !target.hidden() {
+                check(());
+            }
+        });
+        let _ = document.add_event_listener_with_callback(
+            "visibilitychange",
+            on_visibility_change.as_ref().unchecked_ref(),
+        );
+        // the listener lives as long as the app
+        on_visibility_change.forget();
+    });
+    check
+}
+
+/// Badge showing the current and longest [`Streak`] of days with a completed puzzle
+#[component]
+pub fn StreakBadge() -> Element {
+    let stats = use_context::<Signal<Stats>>();
+    let locale = use_context::<Memo<Locale>>();
+    let t = use_t();
+    let streak = stats.read().streak.clone();
+    rsx!(
+        div { class: "streak",
+            img { class: "streak-icon", src: YARN_STREAK, draggable: false }
+            span { class: "streak-current", {locale().number(streak.current)} }
+            span { class: "streak-longest", {t(Msg::StreakBest(streak.longest))} }
+        }
+    )
+}
+
+// ASSETS
+
+const YARN_STREAK: Asset = asset!(
+    "assets/images/yarn_streak.png",
+    ImageAssetOptions::new()
+        .with_size(ImageSize::Manual {
+            width: 96,
+            height: 96
+        })
+        .with_format(ImageFormat::Avif)
+);
@@ -0,0 +1,293 @@
+use dioxus::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+/// A record that is persisted in local storage as a JSON envelope tagged with the version of
+/// its schema, such that saves written by older versions of the app can be migrated and
+/// unreadable saves are reported instead of being silently replaced.
+pub trait Versioned: Serialize + DeserializeOwned + Clone + PartialEq + 'static {
+    /// Key under which the record is stored
+    const KEY: &'static str;
+    /// Current version of the schema, which must be incremented whenever the serialized form
+    /// of the record changes, accompanied by a new case in [`Versioned::migrate`]
+    const VERSION: u32;
+    /// The value used if nothing was stored yet
+    fn initial() -> Self;
+    /// Upgrade `data` stored with schema `version` to schema `version + 1`
+    fn migrate(version: u32, _data: Value) -> Result<Value, String> {
+        Err(format!("no migration from version {version}"))
+    }
+    /// Read data stored before this record was versioned, if any
+    fn legacy() -> Option<Legacy> {
+        None
+    }
+    /// Remove the data read by [`Versioned::legacy`], once it was migrated or backed up
+    fn remove_legacy() {}
+}
+
+/// Data stored before a [`Versioned`] record was versioned
+pub struct Legacy {
+    /// the data as stored, which is backed up if it can't be read
+    pub raw: String,
+    /// the data together with the version of the schema it corresponds to, or the reason why
+    /// it can't be read
+    pub data: Result<(u32, Value), String>,
+}
+
+/// The envelope that every [`Versioned`] record is wrapped in when stored
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    version: u32,
+    data: Value,
+}
+
+/// Reasons why a stored record could not be loaded. In both cases the original data is kept
+/// in storage under [`backup_key`] before it is replaced.
+#[derive(Clone, PartialEq, Debug)]
+pub enum LoadError {
+    /// The stored data could not be read or migrated
+    Corrupt { key: &'static str, reason: String },
+    /// The stored data was written by a newer version of the app
+    TooNew { key: &'static str, version: u32 },
+}
+
+/// Reasons why a record created by [`export`] could not be imported
+#[derive(Clone, PartialEq, Debug)]
+pub enum ImportError {
+    /// The data could not be read or migrated
+    Invalid(String),
+    /// The data was exported by a newer version of the app
+    TooNew(u32),
+}
+
+/// Insert a `field` holding `value` into the object `data`, as used by migrations that add fields
+pub fn add_field(data: &mut Value, field: &str, value: Value) -> Result<(), String> {
+    data.as_object_mut()
+        .ok_or("expected an object")?
+        .insert(field.to_string(), value);
+    Ok(())
+}
+
+/// Key under which the raw data of a record is kept if it could not be loaded
+pub fn backup_key(key: &str) -> String {
+    format!("{key}.backup")
+}
+
+/// Load the record `T`, migrating it from older versions as required. If the stored data
+/// cannot be used, it is backed up and the initial value is returned along with the reason.
+pub fn load<T: Versioned>() -> (T, Option<LoadError>) {
+    let (raw, version, data) = match read_raw(T::KEY) {
+        Some(raw) => match serde_json::from_str::<Envelope>(&raw) {
+            Ok(Envelope { version, data }) => (raw, version, data),
+            Err(e) => return recover(&raw, corrupt::<T>(e)),
+        },
+        None => return load_legacy(),
+    };
+    match upgrade::<T>(version, data) {
+        Ok(value) => (value, None),
+        Err(e) => recover(&raw, e),
+    }
+}
+
+/// Load the record `T` from the data stored before it was versioned, which is removed
+/// afterwards, since the record is stored under its own key from then on
+fn load_legacy<T: Versioned>() -> (T, Option<LoadError>) {
+    let Some(Legacy { raw, data }) = T::legacy() else {
+        return (T::initial(), None);
+    };
+    let loaded = data
+        .map_err(|reason| LoadError::Corrupt {
+            key: T::KEY,
+            reason,
+        })
+        .and_then(|(version, data)| upgrade::<T>(version, data));
+    let loaded = match loaded {
+        Ok(value) => {
+            store(&value);
+            (value, None)
+        }
+        Err(e) => recover(&raw, e),
+    };
+    T::remove_legacy();
+    loaded
+}
+
+/// Write the record `T` to storage, wrapped in an [`Envelope`] with the current version
+pub fn store<T: Versioned>(value: &T) {
+    match serde_json::to_string(&envelope(value)) {
+        Ok(raw) => write_raw(T::KEY, &raw),
+        Err(e) => debug_assert!(false, "failed to serialize {}: {e}", T::KEY),
+    }
+}
+
+/// Serialize the record `T` in the same versioned form it is stored in, such that it can be
+/// imported again later, even by newer versions of the app
+pub fn export<T: Versioned>(value: &T) -> String {
+    serde_json::to_string_pretty(&envelope(value)).unwrap_or_default()
+}
+
+/// Parse a record `T` created by [`export`], migrating it from older versions as required
+pub fn import<T: Versioned>(raw: &str) -> Result<T, ImportError> {
+    let Envelope { version, data } =
+        serde_json::from_str(raw).map_err(|e| ImportError::Invalid(e.to_string()))?;
+    upgrade::<T>(version, data).map_err(|e| match e {
+        LoadError::Corrupt { reason, .. } => ImportError::Invalid(reason),
+        LoadError::TooNew { version, .. } => ImportError::TooNew(version),
+    })
+}
+
+/// Hook that loads the record `T` once and writes it back to storage whenever it changes.
+/// Any [`LoadError`] is pushed to the `Signal<Vec<LoadError>>` in the context, such that it
+/// can be shown to the player.
+pub fn use_versioned<T: Versioned>() -> Signal<T> {
+    let mut issues = use_context::<Signal<Vec<LoadError>>>();
+    let (initial, error) = use_hook(load::<T>);
+    let record = use_signal(move || initial);
+    // report errors once after loading
+    use_effect(move || {
+        if let Some(e) = error.clone() {
+            issues.write().push(e);
+        }
+    });
+    // persist every change
+    use_effect(move || store(&*record.read()));
+    record
+}
+
+/// Apply migrations to `data` stored with schema `version` until it matches the current schema
+/// of `T`, then deserialize it.
+fn upgrade<T: Versioned>(mut version: u32, mut data: Value) -> Result<T, LoadError> {
+    if version > T::VERSION {
+        return Err(LoadError::TooNew {
+            key: T::KEY,
+            version,
+        });
+    }
+    while version < T::VERSION {
+        data = T::migrate(version, data).map_err(|reason| LoadError::Corrupt {
+            key: T::KEY,
+            reason,
+        })?;
+        version += 1;
+    }
+    serde_json::from_value(data).map_err(corrupt::<T>)
+}
+
+fn envelope<T: Versioned>(value: &T) -> Envelope {
+    Envelope {
+        version: T::VERSION,
+        data: serde_json::to_value(value).unwrap_or_default(),
+    }
+}
+
+fn corrupt<T: Versioned>(e: serde_json::Error) -> LoadError {
+    LoadError::Corrupt {
+        key: T::KEY,
+        reason: e.to_string(),
+    }
+}
+
+/// Back up the `raw` data that could not be loaded and fall back to the initial value
+fn recover<T: Versioned>(raw: &str, e: LoadError) -> (T, Option<LoadError>) {
+    write_raw(&backup_key(T::KEY), raw);
+    (T::initial(), Some(e))
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+pub fn read_raw(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok()?
+}
+pub fn remove_raw(key: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(key);
+    }
+}
+fn write_raw(key: &str, raw: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.set_item(key, raw);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        save::SaveGame,
+        settings::{InputMode, Settings},
+        sudoku::Sudoku,
+    };
+    use serde_json::json;
+
+    /// Export `data` stored with schema `version` in an [`Envelope`]
+    fn exported(version: u32, data: Value) -> String {
+        json!({ "version": version, "data": data }).to_string()
+    }
+
+    #[test]
+    fn add_field_to_object() {
+        let mut data = json!({ "a": 1 });
+        assert_eq!(add_field(&mut data, "b", json!(2)), Ok(()));
+        assert_eq!(data, json!({ "a": 1, "b": 2 }));
+        assert!(add_field(&mut json!([1]), "b", json!(2)).is_err());
+    }
+
+    #[test]
+    fn settings_from_version_1() {
+        let data = json!({ "input_mode": InputMode::default() });
+        let settings = import::<Settings>(&exported(1, data)).ok();
+        assert!(settings == Some(Settings::initial()));
+    }
+
+    #[test]
+    fn save_game_from_version_1() {
+        let mut sudoku = Sudoku::empty();
+        sudoku.set(0, 5);
+        let solution: Vec<u8> = (0..81).map(|i| i % 9 + 1).collect();
+        let data = json!({ "sudoku": sudoku, "solution": solution, "playing": true });
+        let game = import::<SaveGame>(&exported(1, data)).ok().unwrap();
+        assert!(game.sudoku == sudoku);
+        assert_eq!(game.solution, solution);
+        assert!(game.playing);
+        assert_eq!(game.elapsed_ms, 0);
+        // filled squares of old games are protected as cues
+        assert!(game.givens[0]);
+        assert_eq!(game.givens.iter().filter(|given| **given).count(), 1);
+        assert!(game.history.is_empty());
+    }
+
+    #[test]
+    fn too_new() {
+        let raw = exported(SaveGame::VERSION + 1, json!({}));
+        assert!(matches!(
+            import::<SaveGame>(&raw),
+            Err(ImportError::TooNew(version)) if version == SaveGame::VERSION + 1
+        ));
+        assert!(matches!(
+            upgrade::<Settings>(Settings::VERSION + 1, json!({})),
+            Err(LoadError::TooNew {
+                key: "settings",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn corrupt() {
+        assert!(matches!(
+            import::<SaveGame>("not json"),
+            Err(ImportError::Invalid(_))
+        ));
+        // the migration to version 5 requires the grid
+        assert!(matches!(
+            upgrade::<SaveGame>(4, json!({})),
+            Err(LoadError::Corrupt { key: "save", reason }) if reason == "missing grid"
+        ));
+        // the current version, but not a game
+        assert!(matches!(
+            upgrade::<SaveGame>(SaveGame::VERSION, json!({ "playing": "yes" })),
+            Err(LoadError::Corrupt { key: "save", .. })
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeSet,
     fmt::Debug,
     ops::{Sub, SubAssign},
     u8,
@@ -6,6 +7,7 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 use serde_big_array::BigArray;
+use strum_macros::Display;
 
 use crate::constants::{PEERS, UNITS};
 
@@ -184,6 +186,20 @@ fn eliminate(grid: &mut [Set; 81], s: usize, d: Set) -> bool {
     true
 }
 
+/// Techniques for solving a sudoku by hand, from the simplest to the hardest
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Technique {
+    /// a square where all but one digit are ruled out by its peers
+    #[strum(to_string = "Naked singles")]
+    NakedSingle,
+    /// a digit that fits in only one square of a unit
+    #[strum(to_string = "Hidden singles")]
+    HiddenSingle,
+    /// anything beyond singles, such as pairs, fish or guessing
+    #[strum(to_string = "Advanced techniques")]
+    Advanced,
+}
+
 /// A sudoku, stored as a flat, row-major array of 81 bytes,
 /// where each `u8` is a value 1-9 or zero for the empty field.
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -219,6 +235,92 @@ impl Sudoku {
         debug_assert!(x < 9 && y < 9);
         self.grid[x + y * 9] == 0
     }
+    /// Get the value of the square at the given x and y index (column and row), where zero
+    /// means empty. This is valid for `x<9` and `y<9` only.
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        debug_assert!(x < 9 && y < 9);
+        self.grid[x + y * 9]
+    }
+    /// Count the number of squares holding the value `val`
+    pub fn count(&self, val: u8) -> usize {
+        self.grid.iter().filter(|n| **n == val).count()
+    }
+    /// Find the index of the closest empty square after the square with index `from` in
+    /// row-major order, or before it if not `forward`, wrapping around the grid. Without a
+    /// starting square, the search starts at the first or last square respectively.
+    pub fn next_empty(&self, from: Option<usize>, forward: bool) -> Option<usize> {
+        let from = from.unwrap_or(if forward { 80 } else { 0 });
+        (1..=81)
+            .map(|k| if forward { (from + k) % 81 } else { (from + 81 - k) % 81 })
+            .find(|i| self.grid[*i] == 0)
+    }
+    /// Check which of the row, column and cell containing the square with index `square`
+    /// are filled, in this order
+    pub fn filled_units(&self, square: usize) -> [bool; 3] {
+        UNITS[square].map(|unit| unit.iter().all(|i| self.grid[*i] > 0))
+    }
+    /// Check for the row, column and cell containing the square with index `square` whether
+    /// all units of the same kind before it are filled, i.e. the rows above, the columns to the
+    /// left and the cells before it in row-major order
+    pub fn filled_before(&self, square: usize) -> [bool; 3] {
+        let (x, y) = (square % 9, square / 9);
+        let indices = [y, x, (y / 3) * 3 + x / 3];
+        [0, 1, 2].map(|kind| {
+            (0..indices[kind]).all(|m| {
+                // any square of the unit with index `m` of this kind
+                let first = [m * 9, m, (m / 3) * 27 + (m % 3) * 3][kind];
+                UNITS[first][kind].iter().all(|i| self.grid[*i] > 0)
+            })
+        })
+    }
+    /// Rate the sudoku by solving it the way a human would with singles only, preferring naked
+    /// singles over hidden ones, and return the techniques that were needed. If the singles
+    /// run out before the sudoku is filled, [`Technique::Advanced`] is needed as well.
+    pub fn techniques(&self) -> Vec<Technique> {
+        let mut sudoku = self.clone();
+        let mut needed = BTreeSet::new();
+        while !sudoku.filled() {
+            let candidates: [Set; 81] = std::array::from_fn(|s| sudoku.candidates(s));
+            let empty = || (0..81).filter(|s| sudoku.grid[*s] == 0);
+            // a square with a single candidate left
+            let naked = empty().find_map(|s| candidates[s].single_to_number().map(|v| (s, v)));
+            // a candidate that fits nowhere else in one of the units of its square
+            let hidden = || {
+                empty().find_map(|s| {
+                    VALS.iter()
+                        .filter(|v| candidates[s].contains(**v))
+                        .find(|v| {
+                            UNITS[s].iter().any(|unit| {
+                                unit.iter().all(|o| {
+                                    *o == s
+                                        || sudoku.grid[*o] != 0
+                                        || candidates[*o].doesnt_contain(**v)
+                                })
+                            })
+                        })
+                        .and_then(|v| v.single_to_number().map(|v| (s, v)))
+                })
+            };
+            if let Some((s, v)) = naked {
+                needed.insert(Technique::NakedSingle);
+                sudoku.set(s, v);
+            } else if let Some((s, v)) = hidden() {
+                needed.insert(Technique::HiddenSingle);
+                sudoku.set(s, v);
+            } else {
+                needed.insert(Technique::Advanced);
+                break;
+            }
+        }
+        needed.into_iter().collect()
+    }
+    /// The digits that are not yet placed in any peer of the square with index `square`
+    fn candidates(&self, square: usize) -> Set {
+        PEERS[square]
+            .iter()
+            .filter(|p| self.grid[**p] > 0)
+            .fold(Set::full(), |set, p| set - Set::new(self.grid[*p]))
+    }
     /// Count the number of filled units, i.e. rows, columns or cells.
     pub fn count_filled_units(&self) -> usize {
         let mut count = 0;
@@ -0,0 +1,170 @@
+use dioxus::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::ShareData;
+
+use crate::{
+    i18n::{use_t, Locale, Msg},
+    save::SaveGame,
+    stats::Stats,
+    sudoku::Technique,
+    timer::format_time,
+    Difficulty,
+};
+
+/// Results of a won game, shown on the card that replaces the finished grid
+#[derive(Clone, PartialEq)]
+pub struct Summary {
+    /// difficulty of the game, unknown for games started before it was saved
+    pub difficulty: Option<Difficulty>,
+    pub elapsed_ms: u64,
+    pub mistakes: u32,
+    pub hints: u32,
+    /// techniques needed to solve the puzzle from its cues
+    pub techniques: Vec<Technique>,
+    /// best time in milliseconds on the same difficulty before this game, if any
+    pub previous_best: Option<u64>,
+}
+impl Summary {
+    /// Summarize the won `game`, comparing it to the `stats` from before it was recorded
+    pub fn new(game: &SaveGame, stats: &Stats) -> Self {
+        Summary {
+            difficulty: game.difficulty,
+            elapsed_ms: game.elapsed_ms,
+            mistakes: game.mistakes,
+            hints: game.hints,
+            techniques: game.puzzle().techniques(),
+            previous_best: game
+                .difficulty
+                .and_then(|difficulty| stats.get(difficulty).best_ms),
+        }
+    }
+    /// Comparison of the time with the previous personal best
+    fn comparison(&self, t: impl Fn(Msg) -> String) -> String {
+        match self.previous_best {
+            None => t(Msg::FirstBest),
+            Some(best) if self.elapsed_ms < best => {
+                t(Msg::NewBest(&format_time(best - self.elapsed_ms)))
+            }
+            Some(best) => t(Msg::BehindBest {
+                by: &format_time(self.elapsed_ms - best),
+                best: &format_time(best),
+            }),
+        }
+    }
+}
+
+/// How the summary was shared
+#[derive(Clone, Copy, PartialEq)]
+enum Shared {
+    /// through the share sheet of the device, which reports back itself
+    Sheet,
+    Copied,
+    /// neither sharing nor copying is possible, or the clipboard refused the text
+    Failed,
+}
+
+/// Share the `text` through the share sheet of the device, or copy it to the clipboard where
+/// sharing isn't supported
+async fn share(text: &str) -> Shared {
+    let Some(navigator) = web_sys::window().map(|window| window.navigator()) else {
+        return Shared::Failed;
+    };
+    // calling a missing method would throw
+    let has = |method: &str| js_sys::Reflect::has(&navigator, &method.into()).unwrap_or(false);
+    if has("share") {
+        let data = ShareData::new();
+        data.set_text(text);
+        let _ = navigator.share_with_data(&data);
+        Shared::Sheet
+    } else if has("clipboard") {
+        match JsFuture::from(navigator.clipboard().write_text(text)).await {
+            Ok(_) => Shared::Copied,
+            Err(_) => Shared::Failed,
+        }
+    } else {
+        Shared::Failed
+    }
+}
+
+/// Card summarizing a won game, from which the next game of the same difficulty can be started
+#[component]
+pub fn SummaryCard(
+    summary: Summary,
+    on_new_game: Callback<Difficulty>,
+    on_menu: Callback,
+) -> Element {
+    let locale = use_context::<Memo<Locale>>();
+    let t = use_t();
+    let mut shared = use_signal(move || None::<Shared>);
+    let difficulty = summary
+        .difficulty
+        .map_or("-".to_string(), |difficulty| t(Msg::Difficulty(difficulty)));
+    let techniques = summary
+        .techniques
+        .iter()
+        .map(|technique| t(Msg::Technique(*technique)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let time = format_time(summary.elapsed_ms);
+    let share_text = t(Msg::ShareText {
+        difficulty: &difficulty,
+        time: &time,
+        mistakes: summary.mistakes,
+        hints: summary.hints,
+    });
+
+    rsx!(
+        div { class: "stats summary",
+            h2 { {t(Msg::PuzzleSolved)} }
+            table { class: "settings",
+                tr {
+                    th { {t(Msg::Time)} }
+                    td { "{time}" }
+                }
+                tr {
+                    th { {t(Msg::Level)} }
+                    td { "{difficulty}" }
+                }
+                tr {
+                    th { {t(Msg::Mistakes)} }
+                    td { {locale().number(summary.mistakes)} }
+                }
+                tr {
+                    th { {t(Msg::Hints)} }
+                    td { {locale().number(summary.hints)} }
+                }
+                tr {
+                    th { {t(Msg::Techniques)} }
+                    td { "{techniques}" }
+                }
+                tr {
+                    th { {t(Msg::Best)} }
+                    td { {summary.comparison(t)} }
+                }
+            }
+            div { class: "stats-actions",
+                if let Some(difficulty) = summary.difficulty {
+                    button {
+                        class: "exit-btn",
+                        onclick: move |_| on_new_game(difficulty),
+                        {t(Msg::NewGame)}
+                    }
+                }
+                button {
+                    class: "exit-btn",
+                    onclick: move |_| {
+                        let text = share_text.clone();
+                        spawn(async move { shared.set(Some(share(&text).await)) });
+                    },
+                    {t(Msg::Share)}
+                }
+                button { class: "exit-btn", onclick: move |_| on_menu(()), {t(Msg::BackToMenu)} }
+            }
+            match shared() {
+                Some(Shared::Copied) => rsx!(p { class: "notice", role: "status", {t(Msg::Copied)} }),
+                Some(Shared::Failed) => rsx!(p { class: "notice", role: "status", {t(Msg::CopyFailed)} }),
+                _ => rsx!(),
+            }
+        }
+    )
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Symbols displayed for the digits 1 to 9 in the grid, notes and number pad. Internally, and
+/// for keyboard input, digits remain numbers.
+#[derive(Default, EnumIter, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SymbolSet {
+    #[default]
+    Western,
+    ArabicIndic,
+    Devanagari,
+    Chinese,
+    Letters,
+    /// cat pictograms, one for each digit
+    Cats,
+}
+impl SymbolSet {
+    /// The symbols for the digits 1 to 9, in order
+    fn symbols(&self) -> [&'static str; 9] {
+        match self {
+            SymbolSet::Western => ["1", "2", "3", "4", "5", "6", "7", "8", "9"],
+            SymbolSet::ArabicIndic => ["١", "٢", "٣", "٤", "٥", "٦", "٧", "٨", "٩"],
+            SymbolSet::Devanagari => ["१", "२", "३", "४", "५", "६", "७", "८", "९"],
+            SymbolSet::Chinese => ["一", "二", "三", "四", "五", "六", "七", "八", "九"],
+            SymbolSet::Letters => ["A", "B", "C", "D", "E", "F", "G", "H", "I"],
+            SymbolSet::Cats => ["😺", "😸", "😹", "😻", "😼", "😽", "🙀", "😿", "😾"],
+        }
+    }
+    /// The symbol displayed for the digit `val` from 1 to 9
+    pub fn symbol(&self, val: u8) -> &'static str {
+        debug_assert!(0 < val && val < 10);
+        self.symbols()[val as usize - 1]
+    }
+    /// Get the next symbol set, cycling back to the first one
+    pub fn next(&self) -> Self {
+        SymbolSet::iter()
+            .cycle()
+            .skip_while(|set| set != self)
+            .nth(1)
+            .unwrap_or_default()
+    }
+    /// A few symbols of the set, used to show it in the settings
+    pub fn sample(&self) -> String {
+        self.symbols()[..3].join(" ")
+    }
+}
@@ -0,0 +1,120 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+use wasm_bindgen::{closure::Closure, JsCast};
+
+use crate::settings::Settings;
+
+// SETTINGS
+
+/// Media query for the dark colour scheme preferred by the system
+const DARK_QUERY: &str = "(prefers-color-scheme: dark)";
+/// Media query for the increased contrast preferred by the system
+const CONTRAST_QUERY: &str = "(prefers-contrast: more)";
+
+/// Colour theme of the app. Each theme is a set of CSS variables in `main.css`, selected by
+/// the `data-theme` attribute of the document element.
+#[derive(EnumIter, Display, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Theme {
+    Light,
+    Dark,
+    #[strum(to_string = "High contrast")]
+    HighContrast,
+    /// avoids telling colours apart by red and green, for deuteranopia and protanopia
+    #[strum(to_string = "Colour-blind safe")]
+    ColourBlind,
+}
+impl Theme {
+    /// Value of the `data-theme` attribute for this theme
+    pub fn attribute(&self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::HighContrast => "high-contrast",
+            Theme::ColourBlind => "colour-blind",
+        }
+    }
+    /// Colours of the colour marks in this theme, as set in `main.css` but without their
+    /// transparency
+    pub fn mark_colours(&self) -> &'static [&'static str] {
+        match self {
+            Theme::Light | Theme::Dark => &[
+                "#ff5f5f", "#ffa64d", "#ffe14d", "#7ddc6f", "#4dd2d2", "#5f8fff", "#b36bff",
+                "#8a8a8a",
+            ],
+            Theme::HighContrast => &[
+                "#ff0000", "#ff8000", "#ffff00", "#00ff00", "#00ffff", "#0080ff", "#ff00ff",
+                "#ffffff",
+            ],
+            Theme::ColourBlind => &[
+                "#e69f00", "#56b4e9", "#009e73", "#f0e442", "#0072b2", "#d55e00", "#cc79a7",
+                "#000000",
+            ],
+        }
+    }
+    /// Get the theme chosen after `choice` in the settings, where `None` follows the system
+    /// and comes before all themes
+    pub fn next_choice(choice: Option<Theme>) -> Option<Theme> {
+        match choice {
+            None => Theme::iter().next(),
+            Some(theme) => Theme::iter().skip_while(|t| *t != theme).nth(1),
+        }
+    }
+}
+
+/// Hook that provides the current [`Theme`] to all components via the context as a
+/// `Memo<Theme>` and applies it to the document. Unless a theme is chosen in the settings, the
+/// one preferred by the system is used, following changes to the preference.
+pub fn use_theme_provider(settings: Signal<Settings>) -> Memo<Theme> {
+    let system = use_system_theme();
+    let theme = use_memo(move || settings.read().theme.unwrap_or(system()));
+    use_effect(move || {
+        let root = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.document_element());
+        if let Some(root) = root {
+            let _ = root.set_attribute("data-theme", theme().attribute());
+        }
+    });
+    use_context_provider(|| theme)
+}
+
+/// Hook holding the [`Theme`] preferred by the system, updated whenever the preference changes
+fn use_system_theme() -> Signal<Theme> {
+    let mut system = use_signal(system_theme);
+    use_hook(move || {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        for query in [DARK_QUERY, CONTRAST_QUERY] {
+            let Ok(Some(list)) = window.match_media(query) else {
+                continue;
+            };
+            let on_change = Closure::<dyn FnMut()>::new(move || system.set(system_theme()));
+            let _ =
+                list.add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref());
+            // the listeners live as long as the app
+            on_change.forget();
+        }
+    });
+    system
+}
+
+/// The [`Theme`] matching the preferences of the system, where increased contrast takes
+/// precedence over the colour scheme
+fn system_theme() -> Theme {
+    if matches(CONTRAST_QUERY) {
+        Theme::HighContrast
+    } else if matches(DARK_QUERY) {
+        Theme::Dark
+    } else {
+        Theme::Light
+    }
+}
+
+fn matches(query: &str) -> bool {
+    web_sys::window()
+        .and_then(|window| window.match_media(query).ok()?)
+        .is_some_and(|list| list.matches())
+}
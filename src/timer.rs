@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use dioxus::prelude::*;
+use dioxus_sdk::utils::timing::use_interval;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+use crate::{
+    i18n::{use_t, Msg},
+    save::SaveGame,
+    settings::Settings,
+};
+
+// SETTINGS
+
+/// Interval in milliseconds at which the elapsed time is updated
+pub const TIMER_TICK: u64 = 1000;
+
+/// The clock measuring the time spent on the current game, shared via the context such that
+/// it can be stopped exactly when the game is won. The time is kept apart from the game until
+/// it is saved, such that ticks neither re-render the grid nor write the game to storage.
+#[derive(Clone, Copy)]
+pub struct Clock {
+    game: Signal<SaveGame>,
+    /// timestamp of the last update of the elapsed time, unset while the clock is stopped
+    last_tick: Signal<Option<f64>>,
+    /// time in milliseconds elapsed since it was last added to the game
+    unsaved_ms: Signal<u64>,
+}
+impl Clock {
+    pub fn new(game: Signal<SaveGame>) -> Self {
+        Clock {
+            game,
+            last_tick: Signal::new(None),
+            unsaved_ms: Signal::new(0),
+        }
+    }
+    /// Add the time since the last tick to the elapsed time, keeping the clock going only if
+    /// `running`, and save it once stopped. Real time is measured, such that delayed ticks or
+    /// ticks cut short by pausing don't distort the result.
+    pub fn tick(&mut self, running: bool) {
+        let now = js_sys::Date::now();
+        if let Some(last) = *self.last_tick.peek() {
+            self.unsaved_ms += (now - last).max(0.) as u64;
+        }
+        self.last_tick.set(if running { Some(now) } else { None });
+        if !running {
+            self.save();
+        }
+    }
+    /// Add the time elapsed since the last save to the game, such as when a digit is placed
+    pub fn save(&mut self) {
+        let unsaved = self.unsaved_ms.take();
+        if unsaved > 0 {
+            self.game.write().elapsed_ms += unsaved;
+        }
+    }
+    /// Time in milliseconds spent on the current game, including the time not saved yet
+    pub fn elapsed_ms(&self) -> u64 {
+        self.game.read().elapsed_ms + *self.unsaved_ms.read()
+    }
+}
+
+/// Timer displayed in the header, which advances the [`Clock`] in the context while the game
+/// is neither paused nor won and shows the elapsed time, unless hidden in the settings.
+#[component]
+pub fn Timer(game: Signal<SaveGame>, paused: Signal<bool>) -> Element {
+    let mut clock = use_context::<Clock>();
+    let settings = use_context::<Signal<Settings>>();
+    let t = use_t();
+    // whether the clock is currently running
+    let running = use_memo(move || {
+        let game = game.read();
+        game.playing && !game.solved() && !paused()
+    });
+    let _interval = use_interval(Duration::from_millis(TIMER_TICK), move || {
+        clock.tick(*running.peek())
+    });
+    // start or stop the clock immediately when the game starts, is paused, resumed or won
+    use_effect(move || clock.tick(running()));
+
+    rsx!(
+        if settings.read().show_timer {
+            span { class: "timer", "{format_time(clock.elapsed_ms())}" }
+        }
+        button {
+            class: "exit-btn",
+            onclick: move |_| paused.toggle(),
+            if *paused.read() { {t(Msg::Resume)} } else { {t(Msg::Pause)} }
+        }
+    )
+}
+
+/// Hook that pauses the game whenever the page is hidden or the window loses focus
+pub fn use_auto_pause(mut paused: Signal<bool>) {
+    use_hook(move || {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let on_blur = Closure::<dyn FnMut()>::new(move || paused.set(true));
+        let _ = window.add_event_listener_with_callback("blur", on_blur.as_ref().unchecked_ref());
+        // the listeners live as long as the app
+        on_blur.forget();
+        if let Some(document) = window.document() {
+            let target = document.clone();
+            let on_visibility_change = Closure::<dyn FnMut()>::new(move || {
+                if target.hidden() {
+                    paused.set(true);
+                }
+            });
+            let _ = document.add_event_listener_with_callback(
+                "visibilitychange",
+                on_visibility_change.as_ref().unchecked_ref(),
+            );
+            on_visibility_change.forget();
+        }
+    })
+}
+
+/// Format a duration in milliseconds as `m:ss`, or `h:mm:ss` if it exceeds an hour
+pub fn format_time(ms: u64) -> String {
+    let s = ms / 1000;
+    let (h, m, s) = (s / 3600, (s / 60) % 60, s % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
+    }
+}
+
+/// Number of days since the unix epoch in the local time zone, which changes at local midnight
+pub fn local_day() -> i64 {
+    let now = js_sys::Date::new_0();
+    let local_ms = now.get_time() - now.get_timezone_offset() * 60_000.;
+    (local_ms / 86_400_000.).floor() as i64
+}
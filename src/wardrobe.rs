@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::{
+    i18n::{use_t, Msg},
+    stats::Stats,
+    storage::Versioned,
+    Difficulty,
+};
+
+/// Where an [`Accessory`] is worn, each of which holds at most one accessory at a time
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Slot {
+    Head,
+    Neck,
+}
+
+/// Cosmetic item that the cat can wear once it was unlocked by its [`Milestone`]
+#[derive(
+    EnumIter, Display, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum Accessory {
+    #[strum(to_string = "Top hat")]
+    TopHat,
+    Bow,
+    Scarf,
+    Crown,
+    #[strum(to_string = "Bell collar")]
+    BellCollar,
+    #[strum(to_string = "Graduation cap")]
+    GraduationCap,
+}
+impl Accessory {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Accessory::TopHat => "🎩",
+            Accessory::Bow => "🎀",
+            Accessory::Scarf => "🧣",
+            Accessory::Crown => "👑",
+            Accessory::BellCollar => "🔔",
+            Accessory::GraduationCap => "🎓",
+        }
+    }
+    pub fn slot(&self) -> Slot {
+        match self {
+            Accessory::TopHat | Accessory::Crown | Accessory::GraduationCap => Slot::Head,
+            Accessory::Bow | Accessory::Scarf | Accessory::BellCollar => Slot::Neck,
+        }
+    }
+    /// The milestone that unlocks the accessory
+    pub fn milestone(&self) -> Milestone {
+        match self {
+            Accessory::TopHat => Milestone::FirstWin,
+            Accessory::Bow => Milestone::Puzzles(10),
+            Accessory::Scarf => Milestone::Streak(3),
+            Accessory::Crown => Milestone::WinAt(Difficulty::Challenge),
+            Accessory::BellCollar => Milestone::Streak(7),
+            Accessory::GraduationCap => Milestone::Puzzles(100),
+        }
+    }
+}
+
+/// Progress in the game that unlocks an [`Accessory`]
+#[derive(Copy, Clone, PartialEq)]
+pub enum Milestone {
+    FirstWin,
+    /// winning the given number of puzzles of any difficulty
+    Puzzles(u32),
+    /// a streak of the given number of days
+    Streak(u32),
+    /// winning a puzzle of the given difficulty
+    WinAt(Difficulty),
+}
+impl Milestone {
+    /// Whether the milestone was reached according to the `stats`
+    pub fn reached(&self, stats: &Stats) -> bool {
+        let won: u32 = stats.difficulties.values().map(|s| s.finished).sum();
+        match self {
+            Milestone::FirstWin => won > 0,
+            Milestone::Puzzles(n) => won >= *n,
+            Milestone::Streak(days) => stats.streak.longest >= *days,
+            Milestone::WinAt(difficulty) => stats.get(*difficulty).finished > 0,
+        }
+    }
+}
+
+/// Accessories owned and worn by the cat, persisted in local storage. Owned accessories stay
+/// unlocked even if the statistics are reset.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Wardrobe {
+    pub owned: BTreeSet<Accessory>,
+    pub equipped: BTreeMap<Slot, Accessory>,
+}
+impl Wardrobe {
+    /// Unlock all accessories whose milestone was reached, returning whether any were new
+    pub fn unlock(&mut self, stats: &Stats) -> bool {
+        let new: Vec<Accessory> = Accessory::iter()
+            .filter(|accessory| !self.owned.contains(accessory))
+            .filter(|accessory| accessory.milestone().reached(stats))
+            .collect();
+        self.owned.extend(&new);
+        !new.is_empty()
+    }
+    /// Put on the `accessory` in place of any other one in its slot, or take it off if it is
+    /// already worn
+    pub fn toggle(&mut self, accessory: Accessory) {
+        if !self.owned.contains(&accessory) {
+            return;
+        }
+        if self.is_equipped(accessory) {
+            self.equipped.remove(&accessory.slot());
+        } else {
+            self.equipped.insert(accessory.slot(), accessory);
+        }
+    }
+    pub fn is_equipped(&self, accessory: Accessory) -> bool {
+        self.equipped.get(&accessory.slot()) == Some(&accessory)
+    }
+}
+impl Versioned for Wardrobe {
+    const KEY: &'static str = "wardrobe";
+    const VERSION: u32 = 1;
+    fn initial() -> Self {
+        Wardrobe::default()
+    }
+}
+
+/// Screen listing all accessories, where owned ones can be put on and taken off and locked ones
+/// show the milestone that unlocks them
+#[component]
+pub fn WardrobeScreen(on_back: Callback) -> Element {
+    let mut wardrobe = use_context::<Signal<Wardrobe>>();
+    let t = use_t();
+
+    rsx!(
+        div { class: "stats",
+            table { class: "settings wardrobe",
+                for accessory in Accessory::iter() {
+                    tr {
+                        th {
+                            span { class: "wardrobe-item", aria_hidden: true, {accessory.emoji()} }
+                            {t(Msg::Accessory(accessory))}
+                        }
+                        td {
+                            if wardrobe.read().owned.contains(&accessory) {
+                                button {
+                                    class: "exit-btn",
+                                    aria_pressed: wardrobe.read().is_equipped(accessory),
+                                    onclick: move |_| wardrobe.write().toggle(accessory),
+                                    if wardrobe.read().is_equipped(accessory) {
+                                        {t(Msg::TakeOff)}
+                                    } else {
+                                        {t(Msg::PutOn)}
+                                    }
+                                }
+                            } else {
+                                span { class: "locked", {t(Msg::Milestone(accessory.milestone()))} }
+                            }
+                        }
+                    }
+                }
+            }
+            button { class: "exit-btn", onclick: move |_| on_back(()), {t(Msg::Back)} }
+        }
+    )
+}